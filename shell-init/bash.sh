@@ -32,7 +32,7 @@ _ggr_completion() {
     prev="${COMP_WORDS[COMP_CWORD-1]}"
 
     # Main commands
-    local commands="init add list cd remove completion shell-init help"
+    local commands="init add list cd remove move rename prune clean lock unlock prompt du run hooks completion shell-init help"
 
     # Options for different commands
     case "${COMP_CWORD}" in
@@ -61,29 +61,148 @@ _ggr_completion() {
                             # Complete commit hashes (simplified)
                             return 0
                             ;;
+                        --remote)
+                            COMPREPLY=( $(compgen -W "$(git remote 2>/dev/null)" -- ${cur}) )
+                            return 0
+                            ;;
+                        --format)
+                            COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
+                            return 0
+                            ;;
                         *)
                             # Complete options
-                            local opts="-b --new-branch -c --commit -h --help"
+                            local opts="-b --new-branch -c --commit --detach --remote --fetch --format -h --help"
                             COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
                             ;;
                     esac
                     ;;
-                list)
-                    # Complete list options
-                    local opts="--names-only -h --help"
+                prompt)
+                    local opts="--refresh -h --help"
                     COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
                     ;;
+                du)
+                    if [[ "${prev}" == "--ignored" ]]; then
+                        COMPREPLY=( $(compgen -W "include exclude only" -- ${cur}) )
+                    elif [[ "${prev}" == "--format" ]]; then
+                        COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
+                    elif [[ "${prev}" == "-d" || "${prev}" == "--depth" ]]; then
+                        return 0
+                    elif [[ "${cur}" == -* ]]; then
+                        local opts="--ignored -d --depth --format -h --help"
+                        COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                    else
+                        local worktrees=$(git-gardener list --names-only 2>/dev/null)
+                        COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                    fi
+                    ;;
+                list)
+                    if [[ "${prev}" == "--columns" ]]; then
+                        # Complete the last of the comma separated columns
+                        local columns="current name branch status ahead-behind age sha subject flags path"
+                        local prefix="" last="${cur}"
+                        if [[ "${cur}" == *,* ]]; then
+                            prefix="${cur%,*},"
+                            last="${cur##*,}"
+                        fi
+                        COMPREPLY=( $(compgen -P "${prefix}" -W "${columns}" -- "${last}") )
+                    elif [[ "${prev}" == "--format" ]]; then
+                        COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
+                    elif [[ "${prev}" == "--sort" ]]; then
+                        COMPREPLY=( $(compgen -W "branch age path status" -- ${cur}) )
+                    elif [[ "${prev}" == "--stale" || "${prev}" == "--match" ]]; then
+                        return 0
+                    else
+                        # Complete list options
+                        local opts="--names-only --porcelain --format --refresh --columns --dirty --clean --merged --stale --locked --match --sort --tree -h --help"
+                        COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                    fi
+                    ;;
                 remove)
                     # Complete worktree names for remove command
                     if [[ ${COMP_CWORD} -eq 2 ]]; then
                         local worktrees=$(git-gardener list --names-only 2>/dev/null)
                         COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                    elif [[ "${prev}" == "--format" ]]; then
+                        COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
                     else
                         # Complete options
-                        local opts="--with-branch -h --help"
+                        local opts="--with-branch -f --force --format -h --help"
                         COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
                     fi
                     ;;
+                move|rename)
+                    # Complete worktree names for move/rename commands
+                    if [[ ${COMP_CWORD} -eq 2 ]]; then
+                        local worktrees=$(git-gardener list --names-only 2>/dev/null)
+                        COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                    elif [[ ${COMP_CWORD} -eq 3 && "${COMP_WORDS[1]}" == "move" ]]; then
+                        COMPREPLY=( $(compgen -d -- ${cur}) )
+                    fi
+                    ;;
+                prune)
+                    if [[ "${prev}" == "--format" ]]; then
+                        COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
+                    else
+                        local opts="-n --dry-run --with-branch -f --force --format -h --help"
+                        COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                    fi
+                    ;;
+                clean)
+                    case "${prev}" in
+                        --into)
+                            local branches=$(git for-each-ref --format='%(refname:short)' refs/heads 2>/dev/null)
+                            COMPREPLY=( $(compgen -W "${branches}" -- ${cur}) )
+                            ;;
+                        --stale)
+                            ;;
+                        --format)
+                            COMPREPLY=( $(compgen -W "text json yaml tsv porcelain" -- ${cur}) )
+                            ;;
+                        *)
+                            local opts="--merged --into --stale -n --dry-run -y --yes --with-branch --format -h --help"
+                            COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                            ;;
+                    esac
+                    ;;
+                lock|unlock)
+                    # Complete worktree names for lock/unlock commands
+                    if [[ ${COMP_CWORD} -eq 2 ]]; then
+                        local worktrees=$(git-gardener list --names-only 2>/dev/null)
+                        COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                    elif [[ "${COMP_WORDS[1]}" == "lock" ]]; then
+                        local opts="-r --reason -h --help"
+                        COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                    fi
+                    ;;
+                run)
+                    case "${prev}" in
+                        -w|--worktree)
+                            # Complete worktree names
+                            local worktrees=$(git-gardener list --names-only 2>/dev/null)
+                            COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                            ;;
+                        *)
+                            if [[ ${COMP_CWORD} -eq 2 ]]; then
+                                # Complete task names
+                                local tasks=$(git-gardener run 2>/dev/null | awk '{print $1}')
+                                COMPREPLY=( $(compgen -W "${tasks}" -- ${cur}) )
+                            else
+                                local opts="-w --worktree -a --all -h --help"
+                                COMPREPLY=( $(compgen -W "${opts}" -- ${cur}) )
+                            fi
+                            ;;
+                    esac
+                    ;;
+                hooks)
+                    if [[ ${COMP_CWORD} -eq 2 ]]; then
+                        # Complete hooks subcommands
+                        COMPREPLY=( $(compgen -W "history" -- ${cur}) )
+                    elif [[ ${COMP_CWORD} -eq 3 && "${COMP_WORDS[2]}" == "history" ]]; then
+                        # Complete worktree names for hooks history
+                        local worktrees=$(git-gardener list --names-only 2>/dev/null)
+                        COMPREPLY=( $(compgen -W "${worktrees}" -- ${cur}) )
+                    fi
+                    ;;
                 completion)
                     # Complete shell names
                     if [[ ${COMP_CWORD} -eq 2 ]]; then
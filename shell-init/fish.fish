@@ -31,7 +31,17 @@ complete -c ggr -f -n "__fish_use_subcommand" -a "init" -d "Initialize git-garde
 complete -c ggr -f -n "__fish_use_subcommand" -a "add" -d "Create a new worktree"
 complete -c ggr -f -n "__fish_use_subcommand" -a "list" -d "List all worktrees"
 complete -c ggr -f -n "__fish_use_subcommand" -a "cd" -d "Change to worktree directory"
+complete -c ggr -f -n "__fish_use_subcommand" -a "prompt" -d "Print a short status of the current worktree"
+complete -c ggr -f -n "__fish_use_subcommand" -a "du" -d "Show disk usage of worktrees"
 complete -c ggr -f -n "__fish_use_subcommand" -a "remove" -d "Remove a worktree"
+complete -c ggr -f -n "__fish_use_subcommand" -a "move" -d "Move a worktree to a new directory"
+complete -c ggr -f -n "__fish_use_subcommand" -a "rename" -d "Rename a worktree's branch and move it to match"
+complete -c ggr -f -n "__fish_use_subcommand" -a "prune" -d "Prune worktrees whose directories are missing"
+complete -c ggr -f -n "__fish_use_subcommand" -a "clean" -d "Remove merged or stale worktrees"
+complete -c ggr -f -n "__fish_use_subcommand" -a "lock" -d "Lock a worktree so it is not pruned or removed"
+complete -c ggr -f -n "__fish_use_subcommand" -a "unlock" -d "Unlock a locked worktree"
+complete -c ggr -f -n "__fish_use_subcommand" -a "run" -d "Run a task defined in .gardener.yml"
+complete -c ggr -f -n "__fish_use_subcommand" -a "hooks" -d "Inspect hook executions"
 complete -c ggr -f -n "__fish_use_subcommand" -a "completion" -d "Generate shell completion scripts"
 complete -c ggr -f -n "__fish_use_subcommand" -a "shell-init" -d "Generate shell integration script"
 complete -c ggr -f -n "__fish_use_subcommand" -a "help" -d "Print help information"
@@ -47,17 +57,77 @@ complete -c ggr -f -n "__fish_seen_subcommand_from cd" -a "(git-gardener list --
 # add command options
 complete -c ggr -n "__fish_seen_subcommand_from add" -s b -l new-branch -d "Create a new branch"
 complete -c ggr -n "__fish_seen_subcommand_from add" -s c -l commit -d "Create from specific commit"
+complete -c ggr -n "__fish_seen_subcommand_from add" -l detach -d "Check out a revision with a detached HEAD"
+complete -c ggr -f -n "__fish_seen_subcommand_from add" -l remote -a "(git remote 2>/dev/null)" -d "Remote to take the branch from"
+complete -c ggr -n "__fish_seen_subcommand_from add" -l fetch -d "Fetch the remote(s) before resolving the branch"
+complete -c ggr -f -n "__fish_seen_subcommand_from add" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
 complete -c ggr -n "__fish_seen_subcommand_from add" -s h -l help -d "Print help"
 
 # list command options
 complete -c ggr -n "__fish_seen_subcommand_from list" -l names-only -d "Output only worktree names"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l porcelain -d "Output in a machine-readable format"
+complete -c ggr -f -n "__fish_seen_subcommand_from list" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l refresh -d "Recompute statuses instead of using the cache"
+complete -c ggr -f -n "__fish_seen_subcommand_from list" -l columns -r -a "current name branch status ahead-behind age sha subject flags path" -d "Columns to show"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l dirty -d "Only show worktrees with uncommitted changes"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l clean -d "Only show worktrees without uncommitted changes"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l merged -d "Only show worktrees whose branches are merged"
+complete -c ggr -f -n "__fish_seen_subcommand_from list" -l stale -r -d "Only show worktrees with no commits for the given days"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l locked -d "Only show locked worktrees"
+complete -c ggr -f -n "__fish_seen_subcommand_from list" -l match -r -d "Only show worktrees matching a glob"
+complete -c ggr -f -n "__fish_seen_subcommand_from list" -l sort -r -a "branch age path status" -d "Sort worktrees"
+complete -c ggr -n "__fish_seen_subcommand_from list" -l tree -d "Group worktrees by branch prefix"
 complete -c ggr -n "__fish_seen_subcommand_from list" -s h -l help -d "Print help"
 
+# prompt command options
+complete -c ggr -f -n "__fish_seen_subcommand_from prompt" -l refresh -d "Recompute the status instead of using the cache"
+
+# du command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from du" -a "(git-gardener list --names-only 2>/dev/null)" -d "Worktree"
+complete -c ggr -f -n "__fish_seen_subcommand_from du" -l ignored -r -a "include exclude only" -d "How to count files ignored by git"
+complete -c ggr -f -n "__fish_seen_subcommand_from du" -s d -l depth -r -d "Also show directories down to this depth"
+complete -c ggr -f -n "__fish_seen_subcommand_from du" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
+complete -c ggr -n "__fish_seen_subcommand_from du" -s h -l help -d "Print help"
+
 # remove command completions
 complete -c ggr -f -n "__fish_seen_subcommand_from remove; and not __fish_seen_argument -l with-branch" -a "(git-gardener list --names-only 2>/dev/null)" -d "Worktree"
 complete -c ggr -n "__fish_seen_subcommand_from remove" -l with-branch -d "Also remove the branch"
+complete -c ggr -n "__fish_seen_subcommand_from remove" -s f -l force -d "Remove even if dirty or locked"
+complete -c ggr -f -n "__fish_seen_subcommand_from remove" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
 complete -c ggr -n "__fish_seen_subcommand_from remove" -s h -l help -d "Print help"
 
+# move/rename command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from move rename; and test (count (commandline -opc)) -eq 2" -a "(git-gardener list --names-only 2>/dev/null)" -d "Worktree"
+
+# prune command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from prune" -s n -l dry-run -d "Show what would be pruned"
+complete -c ggr -f -n "__fish_seen_subcommand_from prune" -l with-branch -d "Also remove the branches"
+complete -c ggr -f -n "__fish_seen_subcommand_from prune" -s f -l force -d "Also prune locked worktrees"
+complete -c ggr -f -n "__fish_seen_subcommand_from prune" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
+
+# clean command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -l merged -d "Remove worktrees whose branches are merged"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -l into -r -a "(git for-each-ref --format='%(refname:short)' refs/heads 2>/dev/null)" -d "Base branch for --merged"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -l stale -r -d "Remove worktrees with no commits for the given days"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -s n -l dry-run -d "Show what would be removed"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -s y -l yes -d "Do not ask for confirmation"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -l with-branch -d "Also remove the branches"
+complete -c ggr -f -n "__fish_seen_subcommand_from clean" -l format -r -a "text json yaml tsv porcelain" -d "Output format"
+
+# lock/unlock command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from lock unlock" -a "(git-gardener list --names-only 2>/dev/null)" -d "Worktree"
+complete -c ggr -f -n "__fish_seen_subcommand_from lock" -s r -l reason -d "Reason for locking"
+
+# run command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from run" -a "(git-gardener run 2>/dev/null | string split -f1 ' ')" -d "Task"
+complete -c ggr -f -n "__fish_seen_subcommand_from run" -s w -l worktree -a "(git-gardener list --names-only 2>/dev/null)" -d "Run in this worktree"
+complete -c ggr -f -n "__fish_seen_subcommand_from run" -s a -l all -d "Run in every worktree"
+
+# hooks command completions
+complete -c ggr -f -n "__fish_seen_subcommand_from hooks; and not __fish_seen_subcommand_from history" -a "history" -d "Show hook execution history"
+complete -c ggr -f -n "__fish_seen_subcommand_from history" -a "(git-gardener list --names-only 2>/dev/null)" -d "Worktree"
+complete -c ggr -n "__fish_seen_subcommand_from history" -s n -l limit -d "Maximum number of runs to show"
+
 # completion command completions
 complete -c ggr -f -n "__fish_seen_subcommand_from completion" -a "bash zsh fish" -d "Shell"
 
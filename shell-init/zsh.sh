@@ -35,7 +35,17 @@ _ggr_completion() {
         'add:Create a new worktree'
         'list:List all worktrees'
         'cd:Change to worktree directory'
+        'prompt:Print a short status of the current worktree'
+        'du:Show disk usage of worktrees'
         'remove:Remove a worktree'
+        'move:Move a worktree to a new directory'
+        'rename:Rename the branch of a worktree and move it to match'
+        'prune:Prune worktrees whose directories are missing'
+        'clean:Remove merged or stale worktrees'
+        'lock:Lock a worktree so it is not pruned or removed'
+        'unlock:Unlock a locked worktree'
+        'run:Run a task defined in .gardener.yml'
+        'hooks:Inspect hook executions'
         'completion:Generate shell completion scripts'
         'shell-init:Generate shell integration script'
         'help:Print help information'
@@ -63,15 +73,44 @@ _ggr_completion() {
                         '--new-branch[Create a new branch]' \
                         '-c[Create from specific commit]:commit:' \
                         '--commit[Create from specific commit]:commit:' \
+                        '--detach[Check out a revision with a detached HEAD]' \
+                        '--remote[Remote to take the branch from]:remote:($(git remote 2>/dev/null))' \
+                        '--fetch[Fetch the remote(s) before resolving the branch]' \
+                        '--format[Output format]:format:(text json yaml tsv porcelain)' \
                         '-h[Print help]' \
                         '--help[Print help]'
                     ;;
                 list)
                     _arguments \
                         '--names-only[Output only worktree names]' \
+                        '--porcelain[Output in a machine-readable format]' \
+                        '--format[Output format]:format:(text json yaml tsv porcelain)' \
+                        '--refresh[Recompute statuses instead of using the cache]' \
+                        '--columns[Columns to show]:columns:_values -s , column current name branch status ahead-behind age sha subject flags path' \
+                        '(--clean)--dirty[Only show worktrees with uncommitted changes]' \
+                        '(--dirty)--clean[Only show worktrees without uncommitted changes]' \
+                        '--merged[Only show worktrees whose branches are merged]' \
+                        '--stale[Only show worktrees with no commits for the given days]:days:' \
+                        '--locked[Only show locked worktrees]' \
+                        '--match[Only show worktrees matching a glob]:glob:' \
+                        '--sort[Sort worktrees]:key:(branch age path status)' \
+                        '--tree[Group worktrees by branch prefix]' \
                         '-h[Print help]' \
                         '--help[Print help]'
                     ;;
+                prompt)
+                    _arguments \
+                        '--refresh[Recompute the status instead of using the cache]' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                du)
+                    _arguments \
+                        '*: :($(git-gardener list --names-only 2>/dev/null))' \
+                        '--ignored[How to count files ignored by git]:mode:(include exclude only)' \
+                        '(-d --depth)'{-d,--depth}'[Also show directories down to this depth]:depth:' \
+                        '--format[Output format]:format:(text json yaml tsv porcelain)' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
                 remove)
                     if [[ $CURRENT -eq 2 ]]; then
                         # Complete worktree names
@@ -81,10 +120,72 @@ _ggr_completion() {
                     else
                         _arguments \
                             '--with-branch[Also remove the branch]' \
+                            '(-f --force)'{-f,--force}'[Remove even if dirty or locked]' \
+                            '--format[Output format]:format:(text json yaml tsv porcelain)' \
                             '-h[Print help]' \
                             '--help[Print help]'
                     fi
                     ;;
+                move)
+                    _arguments \
+                        '1: :($(git-gardener list --names-only 2>/dev/null))' \
+                        '2:new path:_directories' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                rename)
+                    _arguments \
+                        '1: :($(git-gardener list --names-only 2>/dev/null))' \
+                        '2:new branch:' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                prune)
+                    _arguments \
+                        '(-n --dry-run)'{-n,--dry-run}'[Show what would be pruned]' \
+                        '--with-branch[Also remove the branches]' \
+                        '(-f --force)'{-f,--force}'[Also prune locked worktrees]' \
+                        '--format[Output format]:format:(text json yaml tsv porcelain)' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                clean)
+                    _arguments \
+                        '--merged[Remove worktrees whose branches are merged]' \
+                        '--into[Base branch for --merged]:branch:($(git for-each-ref --format="%(refname:short)" refs/heads 2>/dev/null))' \
+                        '--stale[Remove worktrees with no commits for the given days]:days:' \
+                        '(-n --dry-run)'{-n,--dry-run}'[Show what would be removed]' \
+                        '(-y --yes)'{-y,--yes}'[Do not ask for confirmation]' \
+                        '--with-branch[Also remove the branches]' \
+                        '--format[Output format]:format:(text json yaml tsv porcelain)' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                lock)
+                    _arguments \
+                        '1: :($(git-gardener list --names-only 2>/dev/null))' \
+                        '(-r --reason)'{-r,--reason}'[Reason for locking]:reason:' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                unlock)
+                    _arguments \
+                        '1: :($(git-gardener list --names-only 2>/dev/null))' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                run)
+                    local -a tasks
+                    tasks=(${(f)"$(git-gardener run 2>/dev/null | awk '{print $1}')"})
+                    _arguments \
+                        '1: :($tasks)' \
+                        '(-a --all)'{-w,--worktree}'[Run in this worktree]:worktree:($(git-gardener list --names-only 2>/dev/null))' \
+                        '(-w --worktree)'{-a,--all}'[Run in every worktree]' \
+                        '(-h --help)'{-h,--help}'[Print help]'
+                    ;;
+                hooks)
+                    if [[ $CURRENT -eq 2 ]]; then
+                        _values 'hooks command' 'history[Show hook execution history]'
+                    elif [[ $CURRENT -eq 3 && $words[2] == "history" ]]; then
+                        local -a worktrees
+                        worktrees=(${(f)"$(git-gardener list --names-only 2>/dev/null)"})
+                        _describe 'worktree' worktrees
+                    fi
+                    ;;
                 completion|shell-init)
                     # Complete shell names
                     _arguments '1: :(bash zsh fish)'
@@ -0,0 +1,439 @@
+use git2::{ObjectType, Oid};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::error::{GitGardenerError, Result};
+use crate::state::GardenerState;
+
+// フックの入力ハッシュと出力キャッシュを管理する（<git common dir>/gardener/cache/<hash>/）
+pub struct HookCache {
+    state_dir: PathBuf,
+}
+
+// 保持する出力キャッシュの最大件数（超えた分は保存が古い順に削除する）
+const MAX_CACHE_ENTRIES: usize = 10;
+
+// 保存中の一時ディレクトリの接頭辞と、中断されたものとみなして削除するまでの時間
+const STAGING_PREFIX: &str = ".tmp-";
+const STALE_STAGING_AGE: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+// キャッシュ判定の結果
+#[derive(Debug, PartialEq)]
+pub enum CacheOutcome {
+    // 同じ入力で実行済みかつ出力が揃っている
+    UpToDate,
+    // キャッシュから出力を復元した
+    Restored,
+    // フックの実行が必要
+    Miss,
+}
+
+impl HookCache {
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            state_dir: state_dir.to_path_buf(),
+        }
+    }
+
+    // フックの識別子（コマンド・cwd・環境変数・入出力の宣言から決まる）
+    pub fn hook_key(
+        command: &str,
+        cwd: Option<&str>,
+        env: Option<&HashMap<String, String>>,
+        env_files: &[String],
+        inputs: &[String],
+        outputs: &[String],
+    ) -> Result<String> {
+        // HashMapの順序は実行ごとに変わるので並べ替えてから含める
+        let mut env: Vec<String> = env
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        env.sort();
+        let descriptor = format!(
+            "{}\0{}\0{}\0{}\0{}\0{}",
+            command,
+            cwd.unwrap_or(""),
+            env.join("\n"),
+            env_files.join("\n"),
+            inputs.join("\n"),
+            outputs.join("\n")
+        );
+        Ok(Oid::hash_object(ObjectType::Blob, descriptor.as_bytes())?.to_string())
+    }
+
+    // 入力の内容からハッシュを計算する（ディレクトリは中身を再帰的に、存在しないファイルもハッシュに反映する）
+    pub fn inputs_hash(hook_key: &str, worktree_path: &Path, inputs: &[PathBuf]) -> Result<String> {
+        let mut descriptor = format!("{}\n", hook_key);
+
+        for input in inputs {
+            let relative = input.strip_prefix(worktree_path).unwrap_or(input);
+            descriptor.push_str(&format!("{}\0{}\n", relative.display(), content_hash(input)?));
+        }
+
+        Ok(Oid::hash_object(ObjectType::Blob, descriptor.as_bytes())?.to_string())
+    }
+
+    pub fn lookup(
+        &self,
+        hook_key: &str,
+        inputs_hash: &str,
+        worktree_path: &Path,
+        outputs: &[PathBuf],
+    ) -> Result<CacheOutcome> {
+        let state = GardenerState::load(&self.state_dir)?;
+        let ran_with_same_inputs = state
+            .find_hook_run(hook_key, worktree_path)
+            .is_some_and(|entry| entry.inputs_hash == inputs_hash);
+
+        if ran_with_same_inputs && outputs.iter().all(|output| output.exists()) {
+            return Ok(CacheOutcome::UpToDate);
+        }
+
+        if outputs.is_empty() {
+            return Ok(CacheOutcome::Miss);
+        }
+
+        let cache_dir = self.cache_dir(inputs_hash);
+        let cached: Vec<(PathBuf, &PathBuf)> = outputs
+            .iter()
+            .map(|output| {
+                let relative = output.strip_prefix(worktree_path).unwrap_or(output);
+                (cache_dir.join(relative), output)
+            })
+            .collect();
+
+        if !cached.iter().all(|(cached_path, _)| cached_path.exists()) {
+            return Ok(CacheOutcome::Miss);
+        }
+
+        for (cached_path, output) in cached {
+            if output.exists() {
+                remove_path(output)?;
+            }
+            copy_tree(&cached_path, output)?;
+        }
+
+        Ok(CacheOutcome::Restored)
+    }
+
+    // フック成功後に出力をキャッシュへ保存し、実行記録を残す
+    pub fn store(
+        &self,
+        hook_key: &str,
+        inputs_hash: &str,
+        worktree_path: &Path,
+        outputs: &[PathBuf],
+    ) -> Result<()> {
+        let cache_dir = self.cache_dir(inputs_hash);
+
+        for output in outputs {
+            if !output.exists() {
+                return Err(GitGardenerError::Custom(format!(
+                    "Hook did not produce declared output: {}",
+                    output.display()
+                )));
+            }
+        }
+
+        if !outputs.is_empty() && !cache_dir.exists() {
+            // 途中で失敗したコピーが完全なキャッシュとして使われないよう、
+            // キャッシュ内の一時ディレクトリに揃えてからrenameで公開する
+            let staging_dir = self.cache_root().join(format!("{}{}-{}", STAGING_PREFIX, inputs_hash, std::process::id()));
+            if staging_dir.exists() {
+                remove_path(&staging_dir)?;
+            }
+            let staged = outputs.iter().try_for_each(|output| {
+                let relative = output.strip_prefix(worktree_path).unwrap_or(output);
+                copy_tree(output, &staging_dir.join(relative))
+            });
+            // 別のプロセスが先に同じキャッシュを作った場合はそちらを使う
+            if staged.is_err() || std::fs::rename(&staging_dir, &cache_dir).is_err() {
+                let _ = remove_path(&staging_dir);
+            }
+            staged?;
+            self.evict()?;
+        }
+
+        self.record(hook_key, inputs_hash, worktree_path)
+    }
+
+    pub fn record(&self, hook_key: &str, inputs_hash: &str, worktree_path: &Path) -> Result<()> {
+        GardenerState::update(&self.state_dir, |state| {
+            state.record_hook_run(hook_key, worktree_path, inputs_hash);
+            true
+        })
+    }
+
+    fn cache_root(&self) -> PathBuf {
+        self.state_dir.join("cache")
+    }
+
+    fn cache_dir(&self, inputs_hash: &str) -> PathBuf {
+        self.cache_root().join(inputs_hash)
+    }
+
+    // 保存が新しい順にMAX_CACHE_ENTRIES件だけ残し、中断されて残った一時ディレクトリも片付ける
+    fn evict(&self) -> Result<()> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(self.cache_root())? {
+            let entry = entry?;
+            let modified = entry.metadata()?.modified()?;
+            if entry.file_name().to_string_lossy().starts_with(STAGING_PREFIX) {
+                if modified.elapsed().is_ok_and(|age| age > STALE_STAGING_AGE) {
+                    let _ = remove_path(&entry.path());
+                }
+                continue;
+            }
+            entries.push((modified, entry.path()));
+        }
+
+        entries.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        for (_, path) in entries.into_iter().skip(MAX_CACHE_ENTRIES) {
+            remove_path(&path)?;
+        }
+        Ok(())
+    }
+}
+
+// 1つの入力のハッシュ（ディレクトリはパス順に並べた中身の一覧から求める）
+fn content_hash(path: &Path) -> Result<String> {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return Ok("missing".to_string());
+    };
+
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(path)?;
+        Ok(format!("link:{}", target.display()))
+    } else if metadata.is_dir() {
+        let mut entries: Vec<_> = std::fs::read_dir(path)?.collect::<std::io::Result<_>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut listing = String::new();
+        for entry in entries {
+            let name = entry.file_name();
+            listing.push_str(&format!("{}\0{}\n", name.to_string_lossy(), content_hash(&entry.path())?));
+        }
+        Ok(format!("tree:{}", Oid::hash_object(ObjectType::Blob, listing.as_bytes())?))
+    } else {
+        Ok(Oid::hash_file(ObjectType::Blob, path)?.to_string())
+    }
+}
+
+// ディレクトリツリーを複製する。worktree間でinodeを共有すると、node_modulesを書き換えるツールが
+// キャッシュと他のworktreeまで変えてしまうため、ハードリンクではなくreflinkかコピーを使う
+fn copy_tree(source: &Path, dest: &Path) -> Result<()> {
+    let metadata = std::fs::symlink_metadata(source)?;
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if metadata.file_type().is_symlink() {
+        let target = std::fs::read_link(source)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, dest)?;
+        #[cfg(not(unix))]
+        std::fs::copy(source, dest).map(|_| ())?;
+    } else if metadata.is_dir() {
+        std::fs::create_dir_all(dest)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else if !reflink_file(source, dest, &metadata) {
+        std::fs::copy(source, dest)?;
+    }
+
+    Ok(())
+}
+
+// FICLONEでデータブロックを共有したコピーを作る（書き込まれた時点で別のブロックになる）
+#[cfg(target_os = "linux")]
+fn reflink_file(source: &Path, dest: &Path, metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    // _IOW(0x94, 9, int)
+    const FICLONE: libc::c_ulong = 0x4004_9409;
+
+    let (Ok(source_file), Ok(dest_file)) = (std::fs::File::open(source), std::fs::File::create(dest)) else {
+        return false;
+    };
+    // SAFETY: どちらも開いたままのファイルディスクリプタを渡している
+    let cloned = unsafe { libc::ioctl(dest_file.as_raw_fd(), FICLONE as _, source_file.as_raw_fd()) } == 0;
+    if cloned {
+        let _ = std::fs::set_permissions(dest, metadata.permissions());
+    } else {
+        drop(dest_file);
+        let _ = std::fs::remove_file(dest);
+    }
+    cloned
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink_file(_source: &Path, _dest: &Path, _metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if metadata.is_dir() {
+        std::fs::remove_dir_all(path)?;
+    } else {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_inputs_hash_changes_with_content() {
+        // What: 入力ファイルの内容が変わるとハッシュが変わるかテスト
+        let temp_dir = tempdir().unwrap();
+        let lockfile = temp_dir.path().join("package-lock.json");
+        fs::write(&lockfile, "v1").unwrap();
+
+        let first = HookCache::inputs_hash("key", temp_dir.path(), std::slice::from_ref(&lockfile)).unwrap();
+        let same = HookCache::inputs_hash("key", temp_dir.path(), std::slice::from_ref(&lockfile)).unwrap();
+        fs::write(&lockfile, "v2").unwrap();
+        let changed = HookCache::inputs_hash("key", temp_dir.path(), &[lockfile]).unwrap();
+
+        assert_eq!(first, same);
+        assert_ne!(first, changed);
+    }
+
+    #[test]
+    fn test_inputs_hash_ignores_worktree_location() {
+        // What: 同じ内容の入力なら別のworktreeでも同じハッシュになるかテスト
+        let temp_dir = tempdir().unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("Cargo.lock"), "same").unwrap();
+        fs::write(second.join("Cargo.lock"), "same").unwrap();
+
+        let first_hash = HookCache::inputs_hash("key", &first, &[first.join("Cargo.lock")]).unwrap();
+        let second_hash = HookCache::inputs_hash("key", &second, &[second.join("Cargo.lock")]).unwrap();
+
+        assert_eq!(first_hash, second_hash);
+    }
+
+    #[test]
+    fn test_inputs_hash_walks_directories() {
+        // What: ディレクトリの入力は中のファイルの追加・変更でハッシュが変わるかテスト
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::write(src.join("nested/lib.rs"), "v1").unwrap();
+
+        let first = HookCache::inputs_hash("key", temp_dir.path(), std::slice::from_ref(&src)).unwrap();
+        fs::write(src.join("nested/lib.rs"), "v2").unwrap();
+        let changed = HookCache::inputs_hash("key", temp_dir.path(), std::slice::from_ref(&src)).unwrap();
+        fs::write(src.join("main.rs"), "new").unwrap();
+        let added = HookCache::inputs_hash("key", temp_dir.path(), std::slice::from_ref(&src)).unwrap();
+        fs::remove_dir_all(&src).unwrap();
+        let missing = HookCache::inputs_hash("key", temp_dir.path(), &[src]).unwrap();
+
+        assert_ne!(first, changed);
+        assert_ne!(changed, added);
+        assert_ne!(added, missing);
+    }
+
+    #[test]
+    fn test_hook_key_includes_env() {
+        // What: 環境変数やenv_fileの指定が変わるとフックの識別子が変わるかテスト
+        let env = |value: &str| HashMap::from([("NODE_ENV".to_string(), value.to_string())]);
+        let inputs = vec!["package-lock.json".to_string()];
+
+        let dev = HookCache::hook_key("npm ci", None, Some(&env("dev")), &[], &inputs, &[]).unwrap();
+        let prod = HookCache::hook_key("npm ci", None, Some(&env("prod")), &[], &inputs, &[]).unwrap();
+        let with_file = HookCache::hook_key("npm ci", None, Some(&env("dev")), &[".env".to_string()], &inputs, &[]).unwrap();
+
+        assert_ne!(dev, prod);
+        assert_ne!(dev, with_file);
+        assert_eq!(dev, HookCache::hook_key("npm ci", None, Some(&env("dev")), &[], &inputs, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_store_and_restore_outputs() {
+        // What: 保存した出力が別のworktreeに復元されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        fs::create_dir_all(first.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        fs::write(first.join("node_modules/pkg/index.js"), "module").unwrap();
+
+        let cache = HookCache::new(&state_dir);
+        cache
+            .store("key", "hash", &first, &[first.join("node_modules")])
+            .unwrap();
+
+        let outcome = cache
+            .lookup("key", "hash", &second, &[second.join("node_modules")])
+            .unwrap();
+
+        assert_eq!(outcome, CacheOutcome::Restored);
+        let content = fs::read_to_string(second.join("node_modules/pkg/index.js")).unwrap();
+        assert_eq!(content, "module");
+
+        // 復元先をその場で書き換えても、キャッシュや元のworktreeには影響しない
+        fs::write(second.join("node_modules/pkg/index.js"), "patched").unwrap();
+        assert_eq!(fs::read_to_string(first.join("node_modules/pkg/index.js")).unwrap(), "module");
+        assert_eq!(
+            fs::read_to_string(state_dir.join("cache/hash/node_modules/pkg/index.js")).unwrap(),
+            "module"
+        );
+    }
+
+    #[test]
+    fn test_lookup_reports_up_to_date_and_miss() {
+        // What: 同じ入力で実行済みならUpToDate、入力が変わればMissになるかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let worktree = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree).unwrap();
+
+        let cache = HookCache::new(&state_dir);
+        cache.record("key", "hash-1", &worktree).unwrap();
+
+        assert_eq!(cache.lookup("key", "hash-1", &worktree, &[]).unwrap(), CacheOutcome::UpToDate);
+        assert_eq!(cache.lookup("key", "hash-2", &worktree, &[]).unwrap(), CacheOutcome::Miss);
+    }
+
+    #[test]
+    fn test_store_evicts_oldest_entries() {
+        // What: 保存件数が上限を超えると古いキャッシュから削除され、一時ディレクトリが残らないかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let worktree = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(worktree.join("out.txt"), "output").unwrap();
+
+        let cache = HookCache::new(&state_dir);
+        for i in 0..MAX_CACHE_ENTRIES + 2 {
+            cache
+                .store("key", &format!("hash-{}", i), &worktree, &[worktree.join("out.txt")])
+                .unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        let mut names: Vec<String> = fs::read_dir(state_dir.join("cache"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+
+        assert_eq!(names.len(), MAX_CACHE_ENTRIES);
+        assert!(!names.contains(&"hash-0".to_string()));
+        assert!(!names.contains(&"hash-1".to_string()));
+        assert!(names.contains(&format!("hash-{}", MAX_CACHE_ENTRIES + 1)));
+    }
+}
@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
+use git_gardener::disk_usage::IgnoredMode;
+use git_gardener::output::OutputFormat;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -24,23 +27,114 @@ pub enum Commands {
     
     /// Create a new worktree
     Add {
-        /// Branch name
+        /// Branch name (or any revision with --detach)
         branch: String,
         
         /// Create a new branch
         #[arg(short = 'b', long)]
         new_branch: bool,
         
-        /// Create from specific commit
-        #[arg(short = 'c', long)]
+        /// Create the new branch from a specific commit (requires -b)
+        #[arg(short = 'c', long, requires = "new_branch")]
         commit: Option<String>,
+        
+        /// Check out a commit, tag or other revision with a detached HEAD
+        #[arg(long, conflicts_with_all = ["new_branch", "commit"])]
+        detach: bool,
+        
+        /// Remote to take the branch from when it only exists on a remote
+        #[arg(long)]
+        remote: Option<String>,
+        
+        /// Fetch the remote(s) before resolving the branch
+        #[arg(long)]
+        fetch: bool,
+        
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
     
     /// List all worktrees
     List {
         /// Output only worktree names (for shell completion)
-        #[arg(long = "names-only")]
+        #[arg(long = "names-only", conflicts_with_all = ["porcelain", "format"])]
         names_only: bool,
+        
+        /// Output in a stable, machine-readable format (same as --format porcelain)
+        #[arg(long, conflicts_with = "format")]
+        porcelain: bool,
+        
+        /// Output format: text, json, yaml, tsv, porcelain, a format name from .gardener.yml,
+        /// or a template such as '{branch:<}\t{status}{?status.ahead} ↑{status.ahead}{/}'
+        #[arg(long, default_value = "text")]
+        format: String,
+        
+        /// Recompute the status of every worktree instead of using the cache
+        #[arg(long)]
+        refresh: bool,
+        
+        /// Columns to show, comma separated
+        /// (current, name, branch, status, ahead-behind, age, sha, subject, flags, path)
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["names_only", "porcelain", "format"])]
+        columns: Option<Vec<String>>,
+        
+        /// Only show worktrees with uncommitted changes
+        #[arg(long, conflicts_with = "clean")]
+        dirty: bool,
+        
+        /// Only show worktrees without uncommitted changes
+        #[arg(long)]
+        clean: bool,
+        
+        /// Only show worktrees whose branches are merged into the base branch
+        #[arg(long)]
+        merged: bool,
+        
+        /// Only show worktrees with no activity (checkout, commit or staging) for the given number of days
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u32>,
+        
+        /// Only show locked worktrees
+        #[arg(long)]
+        locked: bool,
+        
+        /// Only show worktrees whose branch or name matches a glob (e.g. 'feature/*')
+        #[arg(long = "match", value_name = "GLOB")]
+        pattern: Option<String>,
+        
+        /// Sort worktrees by branch, age (newest commit first), path or status (changes first)
+        #[arg(long, value_enum)]
+        sort: Option<git_gardener::commands::list::SortKey>,
+        
+        /// Group worktrees by the path segments of their branch names
+        #[arg(long, conflicts_with_all = ["names_only", "porcelain", "format", "columns"])]
+        tree: bool,
+    },
+    
+    /// Print a short status of the current worktree (for shell prompts)
+    Prompt {
+        /// Recompute the status instead of using the cache
+        #[arg(long)]
+        refresh: bool,
+    },
+    
+    /// Show disk usage of worktrees (hardlinked or reflinked data is counted once)
+    Du {
+        /// Worktrees to measure (defaults to all worktrees)
+        worktrees: Vec<String>,
+        
+        /// How to count files ignored by git: include, exclude or only
+        #[arg(long, value_enum, default_value_t = IgnoredMode::Include)]
+        ignored: IgnoredMode,
+        
+        /// Also show directories down to this depth inside each worktree
+        #[arg(short = 'd', long, value_name = "N", default_value_t = 0)]
+        depth: usize,
+        
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
     
     /// Change to worktree directory
@@ -57,20 +151,145 @@ pub enum Commands {
         /// Also remove the branch
         #[arg(long = "with-branch")]
         with_branch: bool,
+        
+        /// Remove even if the worktree has uncommitted changes or is locked
+        #[arg(short = 'f', long)]
+        force: bool,
+        
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    
+    /// Prune worktrees whose directories are missing or whose gitdir is broken
+    Prune {
+        /// Show what would be pruned without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        
+        /// Also remove the branches of pruned worktrees
+        #[arg(long = "with-branch")]
+        with_branch: bool,
+        
+        /// Also prune locked worktrees
+        #[arg(short = 'f', long)]
+        force: bool,
+        
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    
+    /// Remove worktrees whose branches are merged or have had no commits for a while
+    #[command(group(ArgGroup::new("criteria").required(true).multiple(true).args(["merged", "stale"])))]
+    Clean {
+        /// Remove worktrees whose branches are merged into the base branch
+        #[arg(long)]
+        merged: bool,
+        
+        /// Base branch for --merged (defaults to the branch of the main worktree)
+        #[arg(long, value_name = "BRANCH", requires = "merged")]
+        into: Option<String>,
+        
+        /// Remove worktrees with no activity (checkout, commit or staging) for the given number of days
+        #[arg(long, value_name = "DAYS")]
+        stale: Option<u32>,
+        
+        /// Show what would be removed without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        
+        /// Do not ask for confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+        
+        /// Also remove the branches of removed worktrees
+        #[arg(long = "with-branch")]
+        with_branch: bool,
+        
+        /// Output format for the result
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    
+    /// Lock a worktree so it is not pruned or removed
+    Lock {
+        /// Worktree to lock
+        worktree: String,
+        
+        /// Reason for locking (e.g. "on a removable disk")
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+    
+    /// Unlock a locked worktree
+    Unlock {
+        /// Worktree to unlock
+        worktree: String,
+    },
+    
+    /// Move a worktree to a new directory
+    Move {
+        /// Worktree to move
+        worktree: String,
+        
+        /// New directory for the worktree
+        new_path: PathBuf,
+    },
+    
+    /// Rename a worktree's branch and move it to match
+    Rename {
+        /// Worktree to rename
+        worktree: String,
+        
+        /// New branch name
+        new_branch: String,
+    },
+    
+    /// Run a task defined in .gardener.yml (lists tasks when no name is given)
+    Run {
+        /// Task name
+        task: Option<String>,
+        
+        /// Run in this worktree instead of the current one
+        #[arg(short = 'w', long, conflicts_with = "all")]
+        worktree: Option<String>,
+        
+        /// Run in every worktree
+        #[arg(short = 'a', long)]
+        all: bool,
+    },
+    
+    /// Inspect hook executions
+    Hooks {
+        #[command(subcommand)]
+        command: HooksCommands,
     },
     
     /// Generate shell completion scripts
     Completion {
         /// Shell to generate completion for
         #[arg(value_enum)]
-        shell: crate::commands::completion::CompletionShell,
+        shell: git_gardener::commands::completion::CompletionShell,
     },
 
     /// Generate shell integration script (enables 'ggr cd' to work directly)
     ShellInit {
         /// Shell to generate integration script for
         #[arg(value_enum)]
-        shell: crate::commands::completion::CompletionShell,
+        shell: git_gardener::commands::completion::CompletionShell,
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum HooksCommands {
+    /// Show hook execution history
+    History {
+        /// Only show runs for this worktree (branch or directory name)
+        worktree: Option<String>,
+        
+        /// Maximum number of runs to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+}
@@ -1,12 +1,19 @@
 use crate::config::Config;
 use crate::error::{GitGardenerError, Result};
-use crate::git::GitWorktree;
+use crate::git::{same_path, GitWorktree};
 use crate::hooks::HookExecutor;
+use crate::output::{self, ChangeAction, OutputFormat, WorktreeChange};
+use crate::signal::{self, SignalGuard};
+use std::path::Path;
 
 pub struct AddCommand {
     pub branch: String,
     pub new_branch: bool,
     pub commit: Option<String>,
+    pub detach: bool,
+    pub remote: Option<String>,
+    pub fetch: bool,
+    pub format: OutputFormat,
 }
 
 impl AddCommand {
@@ -14,44 +21,91 @@ impl AddCommand {
         branch: String,
         new_branch: bool,
         commit: Option<String>,
+        detach: bool,
+        remote: Option<String>,
+        fetch: bool,
     ) -> Self {
         Self {
             branch,
             new_branch,
             commit,
+            detach,
+            remote,
+            fetch,
+            format: OutputFormat::Text,
         }
     }
     
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+    
     pub fn execute(&self) -> Result<()> {
+        output::init(self.format);
         let git_worktree = GitWorktree::new()?;
         let repo_root = git_worktree.get_repository_root()?;
         
         // 設定ファイルを読み込む（存在しない場合はデフォルト設定を使用）
-        let config_path = repo_root.join(".gardener.yml");
-        let config = if config_path.exists() {
-            Config::load_from_file(&config_path)?
-        } else {
-            Config::default()
-        };
+        let config = Config::load_for_repo(&repo_root)?;
         
-        // ブランチが既に存在するかチェック
-        if !self.new_branch && !git_worktree.branch_exists(&self.branch)? {
+        // --commitは新しいブランチの起点なので-bなしでは意味がない
+        if self.commit.is_some() && !self.new_branch {
             return Err(GitGardenerError::Custom(
-                format!(
-                    "Branch '{}' does not exist. Use -b flag to create a new branch.",
-                    self.branch
-                )
+                "--commit can only be used together with -b".to_string()
             ));
         }
         
+        // ブランチ・リビジョンを解決する前にリモートを更新する
+        if self.fetch {
+            let remotes = match &self.remote {
+                Some(remote) => vec![remote.clone()],
+                None => git_worktree.remote_names()?,
+            };
+            for remote in remotes {
+                output::progress(format!("Fetching '{}'...", remote));
+                git_worktree.fetch_remote(&remote)?;
+            }
+        }
+        
+        // --detachの場合はリビジョンを先に解決・検証する
+        let detached = if self.detach {
+            Some(git_worktree.resolve_detach_target(&self.branch)?)
+        } else {
+            None
+        };
+        
+        // ローカルにないブランチはリモートから探し、追跡ブランチを作成する
+        let tracking = if !self.detach && !self.new_branch && !git_worktree.branch_exists(&self.branch)? {
+            let remote_branch = git_worktree
+                .find_remote_branch(&self.branch, self.remote.as_deref())?
+                .ok_or_else(|| GitGardenerError::BranchNotFound {
+                    name: self.branch.clone(),
+                })?;
+            if git_worktree.branch_exists(&remote_branch.name)? {
+                return Err(GitGardenerError::BranchExists { name: remote_branch.name });
+            }
+            Some(remote_branch)
+        } else {
+            None
+        };
+        
+        let branch_name = match &tracking {
+            Some(remote_branch) => remote_branch.name.clone(),
+            None => self.branch.clone(),
+        };
+        
+        // worktreeの名前を決定（ブランチ名、detachedの場合は参照名または`detached-<sha7>`）
+        let worktree_name = match &detached {
+            Some((_, name)) => name.clone(),
+            None => branch_name.clone(),
+        };
+        
         // worktreeのパスを決定（wtpスタイル）
-        let base_dir = config.defaults.root_dir.unwrap_or_else(|| ".gardener".to_string());
+        let base_dir = config.defaults.root_dir.clone().unwrap_or_else(|| ".gardener".to_string());
         let worktree_path = repo_root
             .join(&base_dir)
-            .join(&self.branch);
-        
-        // worktreeの名前を決定（パスのベース名）
-        let worktree_name = self.branch.clone();
+            .join(&worktree_name);
         
         // 既存のworktreeをチェック
         let existing_worktrees = git_worktree.list_worktrees()?;
@@ -62,91 +116,140 @@ impl AddCommand {
         }
         
         // worktreeを作成
-        println!("Creating worktree for branch '{}'...", self.branch);
+        match (&detached, &tracking) {
+            (Some(_), _) => output::progress(format!("Creating detached worktree at '{}'...", self.branch)),
+            (None, Some(remote_branch)) => output::progress(format!(
+                "Creating worktree for branch '{}' tracking '{}'...",
+                branch_name,
+                remote_branch.shorthand()
+            )),
+            (None, None) => output::progress(format!("Creating worktree for branch '{}'...", branch_name)),
+        }
+        
+        // 作成中・フック実行中のCtrl-Cでは途中で終了せず、ロールバックしてから終了する
+        let _signal_guard = SignalGuard::install();
+        let creates_branch = tracking.is_some()
+            || (self.new_branch && !git_worktree.branch_exists(&branch_name)?);
         
         // 親ディレクトリを作成
         if let Some(parent) = worktree_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         
-        git_worktree.create_worktree_with_commit(
-            &worktree_name,
-            &worktree_path,
-            &self.branch,
-            self.new_branch,
-            self.commit.as_deref(),
-        )?;
+        match (&detached, &tracking) {
+            (Some((oid, _)), _) => git_worktree.create_detached_worktree(&worktree_name, &worktree_path, *oid)?,
+            (None, Some(remote_branch)) => {
+                git_worktree.create_tracking_branch(&branch_name, remote_branch)?;
+                let created = git_worktree.create_worktree_with_commit(
+                    &worktree_name,
+                    &worktree_path,
+                    &branch_name,
+                    false,
+                    None,
+                );
+                if let Err(e) = created {
+                    let _ = git_worktree.delete_branch(&branch_name);
+                    return Err(e);
+                }
+            }
+            (None, None) => git_worktree.create_worktree_with_commit(
+                &worktree_name,
+                &worktree_path,
+                &branch_name,
+                self.new_branch,
+                self.commit.as_deref(),
+            )?,
+        }
+        
+        output::progress(format!("✓ Created worktree at {}", worktree_path.display()));
         
-        println!("✓ Created worktree at {}", worktree_path.display());
+        if let Err(e) = self.run_post_create_hooks(&config, &git_worktree, &worktree_path, &worktree_name) {
+            if matches!(e, GitGardenerError::Interrupted { .. }) {
+                eprintln!("Interrupted; rolling back worktree '{}'...", worktree_name);
+            }
+            self.rollback(&git_worktree, &worktree_path, creates_branch.then_some(branch_name.as_str()));
+            return Err(e);
+        }
         
+        // 構造化出力ではlistと同じworktree名（gitの管理ディレクトリ名）を使う
+        let name = git_worktree
+            .list_worktrees()?
+            .into_iter()
+            .find(|w| same_path(&w.path, &worktree_path))
+            .map_or(worktree_name, |w| w.name);
+        let change = WorktreeChange {
+            action: ChangeAction::Created,
+            name,
+            path: worktree_path,
+            branch: detached.is_none().then_some(branch_name),
+            reason: None,
+            branch_created: creates_branch,
+            branch_removed: false,
+        };
+        output::print_changes(self.format, "add", false, &[change])
+    }
+    
+    fn run_post_create_hooks(
+        &self,
+        config: &Config,
+        git_worktree: &GitWorktree,
+        worktree_path: &Path,
+        branch: &str,
+    ) -> Result<()> {
         // post_createフックの実行
         if let Some(ref hooks) = config.hooks {
             if let Some(ref post_create) = hooks.post_create {
-                let hook_executor = HookExecutor::new();
-                hook_executor.execute_hooks(&worktree_path, &self.branch, post_create)?;
+                let hook_executor = HookExecutor::with_state_dir(&git_worktree.get_state_dir());
+                hook_executor.execute_hooks_with_summary(worktree_path, branch, post_create)?;
             }
         }
         
+        // フックがない場合でも、作成中に受けたシグナルは中断として扱う
+        if let Some(signal) = signal::received() {
+            return Err(GitGardenerError::Interrupted { signal });
+        }
+        
         Ok(())
     }
+    
+    // 失敗・中断したaddで作成したworktree（と新規作成したブランチ）を取り除く
+    fn rollback(&self, git_worktree: &GitWorktree, worktree_path: &Path, created_branch: Option<&str>) {
+        let removed = git_worktree
+            .list_worktrees()
+            .ok()
+            .and_then(|worktrees| worktrees.into_iter().find(|w| same_path(&w.path, worktree_path)))
+            .map(|worktree| git_worktree.remove_worktree(&worktree.name, true));
+        
+        match removed {
+            Some(Ok(())) => eprintln!("✓ Rolled back worktree at {}", worktree_path.display()),
+            Some(Err(e)) => eprintln!("Failed to roll back worktree at {}: {}", worktree_path.display(), e),
+            None => {}
+        }
+        
+        if let Some(branch) = created_branch {
+            match git_worktree.delete_branch(branch) {
+                Ok(()) => eprintln!("✓ Rolled back branch '{}'", branch),
+                Err(e) => eprintln!("Failed to roll back branch '{}': {}", branch, e),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{git, setup_git_repo};
     use tempfile::tempdir;
     use std::fs;
     use std::process::Command;
 
-    fn setup_git_repo() -> tempfile::TempDir {
-        let temp_dir = tempdir().unwrap();
-        let repo_path = temp_dir.path();
-        
-        // Git リポジトリを初期化
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .expect("Failed to init git repo");
-        
-        // 設定
-        Command::new("git")
-            .args(&["config", "user.name", "Test User"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["config", "user.email", "test@example.com"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // 初期ファイルとコミットを作成
-        fs::write(repo_path.join("README.md"), "# Test Repo").unwrap();
-        
-        Command::new("git")
-            .args(&["add", "."])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["commit", "-m", "Initial commit"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        temp_dir
-    }
-
     #[test]
     fn test_add_command_new_creates_instance() {
         // What: AddCommand::newが正しくインスタンスを作成するかテスト
-        let cmd = AddCommand::new("test-branch".to_string(), true, None);
+        let cmd = AddCommand::new("test-branch".to_string(), true, None, false, None, false);
         
         assert_eq!(cmd.branch, "test-branch");
-        assert_eq!(cmd.new_branch, true);
+        assert!(cmd.new_branch);
         assert_eq!(cmd.commit, None);
     }
 
@@ -156,11 +259,14 @@ mod tests {
         let cmd = AddCommand::new(
             "feature-branch".to_string(), 
             false, 
-            Some("abc123".to_string())
+            Some("abc123".to_string()),
+            false,
+            None,
+            false,
         );
         
         assert_eq!(cmd.branch, "feature-branch");
-        assert_eq!(cmd.new_branch, false);
+        assert!(!cmd.new_branch);
         assert_eq!(cmd.commit, Some("abc123".to_string()));
     }
 
@@ -170,7 +276,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let cmd = AddCommand::new("test".to_string(), true, None);
+        let cmd = AddCommand::new("test".to_string(), true, None, false, None, false);
         let result = cmd.execute();
         
         assert!(result.is_err());
@@ -183,7 +289,7 @@ mod tests {
         let temp_dir = setup_git_repo();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let cmd = AddCommand::new("nonexistent-branch".to_string(), false, None);
+        let cmd = AddCommand::new("nonexistent-branch".to_string(), false, None, false, None, false);
         let result = cmd.execute();
         
         assert!(result.is_err());
@@ -191,4 +297,157 @@ mod tests {
         assert!(error_msg.contains("does not exist"));
         assert!(error_msg.contains("Use -b flag"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_add_command_rolls_back_when_hook_fails() {
+        // What: フックが失敗した場合にworktreeと新規ブランチがロールバックされるかテスト
+        let temp_dir = setup_git_repo();
+        fs::write(
+            temp_dir.path().join(".gardener.yml"),
+            "hooks:\n  post_create:\n    - type: command\n      command: \"exit 1\"\n",
+        ).unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let cmd = AddCommand::new("rollback-test".to_string(), true, None, false, None, false);
+        let result = cmd.execute();
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::HookFailed { .. }));
+        assert!(!temp_dir.path().join(".gardener/rollback-test").exists());
+        
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        assert!(!git_worktree.branch_exists("rollback-test").unwrap());
+        assert!(git_worktree.list_worktrees().unwrap().iter().all(|w| w.is_main));
+    }
+
+    #[test]
+    fn test_add_command_from_linked_worktree() {
+        // What: リンクされたworktree内から実行してもメインの設定を読み、メイン配下に作成するかテスト
+        let temp_dir = setup_git_repo();
+        let linked_path = temp_dir.path().join(".gardener/first");
+        git(temp_dir.path(), &["worktree", "add", "-b", "first", &linked_path.to_string_lossy()]);
+        // コミットされていない設定はメインworktreeにしか存在しない
+        fs::write(
+            temp_dir.path().join(".gardener.yml"),
+            "hooks:\n  post_create:\n    - type: command\n      command: \"touch from-main-config\"\n",
+        ).unwrap();
+        std::env::set_current_dir(&linked_path).unwrap();
+        
+        let cmd = AddCommand::new("second".to_string(), true, None, false, None, false);
+        let result = cmd.execute();
+        
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join(".gardener/second/from-main-config").exists());
+        assert!(!linked_path.join(".gardener/second").exists());
+    }
+
+    #[test]
+    fn test_add_command_commit_requires_new_branch() {
+        // What: -bなしの--commitが無視されずにエラーになるかテスト
+        let temp_dir = setup_git_repo();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let cmd = AddCommand::new("feature".to_string(), false, Some("HEAD".to_string()), false, None, false);
+        let result = cmd.execute();
+        
+        assert!(format!("{}", result.unwrap_err()).contains("--commit"));
+    }
+
+    #[test]
+    fn test_add_command_detach_uses_tag_name() {
+        // What: --detachでタグを指定するとタグ名のディレクトリにdetached HEADで作成されるかテスト
+        let temp_dir = setup_git_repo();
+        git(temp_dir.path(), &["tag", "v1.0"]);
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let cmd = AddCommand::new("v1.0".to_string(), false, None, true, None, false);
+        let result = cmd.execute();
+        
+        assert!(result.is_ok());
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let worktree = git_worktree
+            .list_worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|w| w.name == "v1.0")
+            .unwrap();
+        assert!(worktree.is_detached);
+        assert!(worktree.branch.starts_with("(detached at "));
+        assert!(temp_dir.path().join(".gardener/v1.0/README.md").exists());
+    }
+
+    #[test]
+    fn test_add_command_detach_rejects_invalid_revision() {
+        // What: 解決できないリビジョンは作成前にエラーになるかテスト
+        let temp_dir = setup_git_repo();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let cmd = AddCommand::new("no-such-rev".to_string(), false, None, true, None, false);
+        let result = cmd.execute();
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidRevision { .. }));
+        assert!(!temp_dir.path().join(".gardener").exists());
+    }
+
+    // ローカルのbareリポジトリをリモートとして追加し、リモートにだけfeature-xブランチを作る
+    fn add_bare_remote(repo_path: &Path, remote_dir: &Path, remote: &str) {
+        let bare_path = remote_dir.join(format!("{}.git", remote));
+        Command::new("git")
+            .args(["init", "--bare", &bare_path.to_string_lossy()])
+            .output()
+            .unwrap();
+        let tracking_ref = format!("refs/remotes/{}/feature-x", remote);
+        for args in [
+            vec!["remote", "add", remote, &bare_path.to_string_lossy()],
+            vec!["push", remote, "HEAD:refs/heads/feature-x"],
+            // pushで作られた追跡ブランチを消し、fetchするまで見えない状態にする
+            vec!["update-ref", "-d", &tracking_ref],
+        ] {
+            git(repo_path, &args);
+        }
+    }
+
+    #[test]
+    fn test_add_command_tracks_remote_only_branch_after_fetch() {
+        // What: リモートにだけあるブランチが--fetch後に追跡ブランチとして作成されるかテスト
+        let temp_dir = setup_git_repo();
+        let remote_dir = tempdir().unwrap();
+        add_bare_remote(temp_dir.path(), remote_dir.path(), "origin");
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        // fetchするまではリモート追跡ブランチがない
+        let result = AddCommand::new("feature-x".to_string(), false, None, false, None, false).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::BranchNotFound { .. }));
+        
+        let result = AddCommand::new("feature-x".to_string(), false, None, false, None, true).execute();
+        
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join(".gardener/feature-x/README.md").exists());
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        let branch = repo.find_branch("feature-x", git2::BranchType::Local).unwrap();
+        assert_eq!(branch.upstream().unwrap().name().unwrap(), Some("origin/feature-x"));
+    }
+
+    #[test]
+    fn test_add_command_chooses_among_multiple_remotes() {
+        // What: 複数のリモートにある場合はエラーになり、--remoteやリモート名付きで選べるかテスト
+        let temp_dir = setup_git_repo();
+        let remote_dir = tempdir().unwrap();
+        add_bare_remote(temp_dir.path(), remote_dir.path(), "origin");
+        add_bare_remote(temp_dir.path(), remote_dir.path(), "upstream");
+        git(temp_dir.path(), &["fetch", "--all"]);
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = AddCommand::new("feature-x".to_string(), false, None, false, None, false).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::AmbiguousRemoteBranch { .. }));
+        
+        let result = AddCommand::new("feature-x".to_string(), false, None, false, Some("upstream".to_string()), false).execute();
+        assert!(result.is_ok());
+        let repo = git2::Repository::open(temp_dir.path()).unwrap();
+        let branch = repo.find_branch("feature-x", git2::BranchType::Local).unwrap();
+        assert_eq!(branch.upstream().unwrap().name().unwrap(), Some("upstream/feature-x"));
+        
+        // ローカルブランチが既にある場合はリモート名付きでも作成しない
+        let result = AddCommand::new("origin/feature-x".to_string(), false, None, false, None, false).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::BranchExists { .. }));
+    }
+}
@@ -37,60 +37,9 @@ impl CdCommand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::setup_git_repo_with_worktree;
     use tempfile::tempdir;
-    use std::fs;
-    use std::process::Command;
-
-    fn setup_git_repo_with_worktree() -> tempfile::TempDir {
-        let temp_dir = tempdir().unwrap();
-        let repo_path = temp_dir.path();
-        
-        // Git リポジトリを初期化
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .expect("Failed to init git repo");
-        
-        // 設定
-        Command::new("git")
-            .args(&["config", "user.name", "Test User"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["config", "user.email", "test@example.com"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // 初期ファイルとコミットを作成
-        fs::write(repo_path.join("README.md"), "# Test Repo").unwrap();
-        
-        Command::new("git")
-            .args(&["add", "."])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["commit", "-m", "Initial commit"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // テスト用のworktreeを作成
-        let worktree_path = repo_path.join("feature-test");
-        Command::new("git")
-            .args(&["worktree", "add", "-b", "feature-test", &worktree_path.to_string_lossy()])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        temp_dir
-    }
-
+    
     #[test]
     fn test_cd_command_new_creates_instance() {
         // What: CdCommand::newが正しくインスタンスを作成するかテスト
@@ -153,4 +102,20 @@ mod tests {
         let error_msg = format!("{}", result.unwrap_err());
         assert!(error_msg.contains("not found"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cd_command_from_linked_worktree() {
+        // What: リンクされたworktree内から@でメインworktreeのパスを返すかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path().join("feature-test")).unwrap();
+        
+        let main_path = CdCommand::new("@".to_string()).execute().unwrap();
+        let worktree_path = CdCommand::new("feature-test".to_string()).execute().unwrap();
+        
+        assert_eq!(
+            std::path::Path::new(&main_path).canonicalize().unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+        assert!(worktree_path.ends_with("feature-test"));
+    }
+}
@@ -0,0 +1,435 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::status::{GitStatus, WorktreeStatus};
+use crate::git::{remove_empty_parents, GitWorktree, WorktreeInfo};
+use crate::output::{self, ChangeAction, OutputFormat, WorktreeChange};
+use crate::state;
+use colored::*;
+use std::io::{BufRead, Write};
+
+pub struct CleanCommand {
+    pub merged: bool,
+    pub into: Option<String>,
+    pub stale: Option<u32>,
+    pub dry_run: bool,
+    pub yes: bool,
+    pub with_branch: bool,
+    pub format: OutputFormat,
+}
+
+// 削除対象になったworktreeとその理由
+pub struct CleanCandidate {
+    pub worktree: WorktreeInfo,
+    pub reason: String,
+    // ロック中・未コミットの変更があるため残す場合の理由
+    pub skip_reason: Option<&'static str>,
+}
+
+impl CleanCommand {
+    pub fn new(
+        merged: bool,
+        into: Option<String>,
+        stale: Option<u32>,
+        dry_run: bool,
+        yes: bool,
+        with_branch: bool,
+    ) -> Self {
+        Self {
+            merged,
+            into,
+            stale,
+            dry_run,
+            yes,
+            with_branch,
+            format: OutputFormat::Text,
+        }
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        output::init(self.format);
+        let git_worktree = GitWorktree::new()?;
+        let repo_root = git_worktree.get_repository_root()?;
+        let config = Config::load_for_repo(&repo_root)?;
+        let base_dir = repo_root.join(config.defaults.root_dir.unwrap_or_else(|| ".gardener".to_string()));
+
+        let (skipped, candidates): (Vec<_>, Vec<_>) = self
+            .find_candidates(&git_worktree, config.defaults.base_branch.as_deref())?
+            .into_iter()
+            .partition(|candidate| candidate.skip_reason.is_some());
+        let mut changes = Vec::new();
+        for candidate in &skipped {
+            let change = candidate.skipped_change();
+            output::progress(format!(
+                "{} Skipped worktree '{}' ({})",
+                "-".yellow(),
+                candidate.worktree.branch,
+                change.reason.as_deref().unwrap_or_default()
+            ));
+            changes.push(change);
+        }
+        if candidates.is_empty() {
+            output::progress("Nothing to clean.");
+            return output::print_changes(self.format, "clean", self.dry_run, &changes);
+        }
+
+        for candidate in &candidates {
+            output::progress(format!(
+                "{} {} ({})",
+                candidate.worktree.branch.green(),
+                candidate.worktree.path.display(),
+                candidate.reason
+            ));
+        }
+
+        if self.dry_run {
+            output::progress(format!("Would remove {} worktree(s)", candidates.len()));
+            changes.extend(candidates.iter().map(|candidate| WorktreeChange {
+                branch_removed: self.with_branch,
+                ..candidate.change()
+            }));
+            return output::print_changes(self.format, "clean", true, &changes);
+        }
+        if !self.yes && !confirm(&format!("Remove {} worktree(s)?", candidates.len()))? {
+            output::progress("Aborted.");
+            return output::print_changes(self.format, "clean", false, &changes);
+        }
+
+        // 1つ削除できなくても残りは続け、失敗した数を最後に返す
+        let mut failed = 0;
+        for candidate in candidates {
+            let mut change = candidate.change();
+            let worktree = candidate.worktree;
+            if let Err(e) = git_worktree.remove_worktree(&worktree.name, false) {
+                eprintln!("Failed to remove worktree '{}': {}", worktree.branch, e);
+                failed += 1;
+                continue;
+            }
+            output::progress(format!("✓ Removed worktree '{}'", worktree.branch));
+            if let Err(e) = state::forget_worktree(&git_worktree.get_state_dir(), &worktree.path) {
+                eprintln!("Failed to clear hook cache state for '{}': {}", worktree.branch, e);
+            }
+
+            if self.with_branch {
+                match git_worktree.delete_branch(&worktree.branch) {
+                    Ok(()) => {
+                        output::progress(format!("✓ Removed branch '{}'", worktree.branch));
+                        change.branch_removed = true;
+                    }
+                    Err(e) => eprintln!("Failed to remove branch '{}': {}", worktree.branch, e),
+                }
+            }
+
+            if worktree.path.starts_with(&base_dir) {
+                remove_empty_parents(&worktree.path, &base_dir);
+            }
+            changes.push(change);
+        }
+
+        output::print_changes(self.format, "clean", false, &changes)?;
+        if failed > 0 {
+            return Err(GitGardenerError::WorktreesFailed { action: "remove", count: failed });
+        }
+        Ok(())
+    }
+
+    // マージ済み・放置されたworktreeを探す（未コミットの変更があるものやロック中のものはskip_reason付き）
+    pub fn find_candidates(&self, git_worktree: &GitWorktree, default_base: Option<&str>) -> Result<Vec<CleanCandidate>> {
+        let worktrees = git_worktree.list_worktrees()?;
+        // ベースブランチは--mergedの判定にだけ使う（--staleだけなら決められなくてもよい）
+        let base_branch = if self.merged {
+            Some(self.base_branch(git_worktree, &worktrees, default_base)?)
+        } else {
+            None
+        };
+
+        let mut candidates = Vec::new();
+        for worktree in worktrees {
+            // ディレクトリのないworktreeはpruneの対象、コマンドを実行中のworktreeは削除しない
+            if worktree.is_main || worktree.is_current || worktree.is_detached || worktree.is_prunable {
+                continue;
+            }
+            if base_branch.as_ref() == Some(&worktree.branch) {
+                continue;
+            }
+
+            let mut reasons = Vec::new();
+            if let Some(base_branch) = &base_branch {
+                if let Some(method) = git_worktree.branch_merge_method(&worktree.branch, base_branch)? {
+                    reasons.push(format!("merged into {} ({})", base_branch, method));
+                }
+            }
+            if let Some(days) = self.stale {
+                if git_worktree.is_worktree_stale(&worktree, days) {
+                    reasons.push(format!("inactive for {} days", days));
+                }
+            }
+            if reasons.is_empty() {
+                continue;
+            }
+
+            let skip_reason = if worktree.is_locked {
+                Some("locked")
+            } else {
+                let is_dirty = GitStatus::for_removal(&worktree.path)
+                    .map(|status| status.working_tree_status == WorktreeStatus::Dirty)
+                    .unwrap_or(true);
+                is_dirty.then_some("uncommitted changes")
+            };
+
+            candidates.push(CleanCandidate {
+                worktree,
+                reason: reasons.join(", "),
+                skip_reason,
+            });
+        }
+
+        Ok(candidates)
+    }
+
+    // --into、設定のdefaults.base_branch、メインworktreeのブランチの順に決める
+    fn base_branch(
+        &self,
+        git_worktree: &GitWorktree,
+        worktrees: &[WorktreeInfo],
+        default_base: Option<&str>,
+    ) -> Result<String> {
+        let base_branch = match self.into.as_deref().or(default_base) {
+            Some(branch) => branch.to_string(),
+            None => worktrees
+                .iter()
+                .find(|w| w.is_main && !w.is_detached)
+                .map(|w| w.branch.clone())
+                .ok_or_else(|| GitGardenerError::Custom("Cannot determine the base branch; use --into".to_string()))?,
+        };
+        if !git_worktree.branch_exists(&base_branch)? {
+            return Err(GitGardenerError::BranchNotFound { name: base_branch });
+        }
+        Ok(base_branch)
+    }
+}
+
+impl CleanCandidate {
+    // 残したworktreeの記録（pruneと同じく理由はスキップした理由）
+    fn skipped_change(&self) -> WorktreeChange {
+        WorktreeChange {
+            action: ChangeAction::Skipped,
+            reason: self.skip_reason.map(|reason| reason.to_string()),
+            ..self.change()
+        }
+    }
+
+    fn change(&self) -> WorktreeChange {
+        WorktreeChange {
+            action: ChangeAction::Removed,
+            name: self.worktree.name.clone(),
+            path: self.worktree.path.clone(),
+            branch: Some(self.worktree.branch.clone()),
+            reason: Some(self.reason.clone()),
+            branch_created: false,
+            branch_removed: false,
+        }
+    }
+}
+
+// 標準入力でy/yesが入力された場合のみtrue（構造化出力を汚さないよう確認は標準エラーに出す）
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, setup_git_repo};
+    use std::fs;
+
+    // mainにマージ済みのfeature/merged、未マージのactive、変更のあるdirtyのworktreeを作る
+    fn setup_git_repo_with_worktrees() -> tempfile::TempDir {
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+
+        for branch in ["feature/merged", "active", "dirty"] {
+            let worktree_path = repo_path.join(".gardener").join(branch);
+            git(repo_path, &["worktree", "add", "-b", branch, &worktree_path.to_string_lossy()]);
+        }
+
+        // マージされたブランチにはコミットがある（--no-ffでマージコミットを作って取り込む）
+        let merged_path = repo_path.join(".gardener/feature/merged");
+        fs::write(merged_path.join("done.txt"), "done").unwrap();
+        git(&merged_path, &["add", "."]);
+        git(&merged_path, &["commit", "-m", "Finished work"]);
+        git(repo_path, &["merge", "--no-ff", "-m", "Merge feature/merged", "feature/merged"]);
+
+        let active_path = repo_path.join(".gardener/active");
+        fs::write(active_path.join("work.txt"), "work").unwrap();
+        git(&active_path, &["add", "."]);
+        git(&active_path, &["commit", "-m", "Work in progress"]);
+
+        fs::write(repo_path.join(".gardener/dirty/scratch.txt"), "scratch").unwrap();
+
+        temp_dir
+    }
+
+    // (ブランチ名, スキップの理由)をブランチ名順に並べる
+    fn skip_reasons(candidates: &[CleanCandidate]) -> Vec<(String, Option<&'static str>)> {
+        let mut reasons: Vec<_> = candidates.iter().map(|c| (c.worktree.branch.clone(), c.skip_reason)).collect();
+        reasons.sort();
+        reasons
+    }
+
+    #[test]
+    fn test_clean_command_finds_merged_worktrees() {
+        // What: --mergedでマージ済みのworktreeが候補になり、変更のあるものはスキップの理由付きになるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = CleanCommand::new(true, None, None, true, false, false);
+        let candidates = cmd.find_candidates(&GitWorktree::new().unwrap(), None).unwrap();
+
+        assert_eq!(
+            skip_reasons(&candidates),
+            vec![("dirty".to_string(), Some("uncommitted changes")), ("feature/merged".to_string(), None)]
+        );
+        let merged = candidates.iter().find(|c| c.skip_reason.is_none()).unwrap();
+        assert_eq!(merged.reason, "merged into main (merge)");
+    }
+
+    #[test]
+    fn test_clean_command_removes_worktrees_and_branches() {
+        // What: --yesで候補のworktreeとブランチ、空になったディレクトリが削除されるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = CleanCommand::new(true, Some("main".to_string()), None, false, true, true).execute();
+
+        assert!(result.is_ok());
+        let git_worktree = GitWorktree::new().unwrap();
+        assert_eq!(git_worktree.list_worktrees().unwrap().len(), 3);
+        assert!(!git_worktree.branch_exists("feature/merged").unwrap());
+        assert!(!temp_dir.path().join(".gardener/feature").exists());
+        assert!(temp_dir.path().join(".gardener/dirty/scratch.txt").exists());
+    }
+
+    #[test]
+    fn test_clean_command_stale_and_locked() {
+        // What: --staleで古いworktreeが候補になり、ロック中のものはスキップされるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        git_worktree.lock_worktree("merged", None).unwrap();
+
+        let fresh = CleanCommand::new(false, None, Some(30), true, false, false);
+        assert!(fresh.find_candidates(&git_worktree, None).unwrap().is_empty());
+
+        // 0日指定なら直前の作業も古いとみなされる
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        let stale = CleanCommand::new(false, None, Some(0), true, false, false);
+        let candidates = stale.find_candidates(&git_worktree, None).unwrap();
+        assert_eq!(
+            skip_reasons(&candidates),
+            vec![
+                ("active".to_string(), None),
+                ("dirty".to_string(), Some("uncommitted changes")),
+                ("feature/merged".to_string(), Some("locked")),
+            ]
+        );
+        assert!(candidates.iter().all(|c| c.reason == "inactive for 0 days"));
+    }
+
+    #[test]
+    fn test_clean_command_stale_uses_worktree_activity() {
+        // What: 古いコミットでも最近チェックアウトしたworktreeは放置とみなされないかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        std::process::Command::new("git")
+            .args(["commit", "--allow-empty", "-m", "Old work"])
+            .env("GIT_AUTHOR_DATE", "2000-01-01T00:00:00")
+            .env("GIT_COMMITTER_DATE", "2000-01-01T00:00:00")
+            .current_dir(repo_path)
+            .output()
+            .unwrap();
+        git(repo_path, &["branch", "old-work"]);
+        git(repo_path, &["worktree", "add", &repo_path.join(".gardener/old-work").to_string_lossy(), "old-work"]);
+        std::env::set_current_dir(repo_path).unwrap();
+
+        let cmd = CleanCommand::new(false, None, Some(30), true, false, false);
+
+        assert!(cmd.find_candidates(&GitWorktree::new().unwrap(), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_clean_command_skips_current_worktree() {
+        // What: コマンドを実行中のworktreeは候補にならないかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path().join(".gardener/active")).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+
+        let cmd = CleanCommand::new(false, None, Some(0), true, false, false);
+        let candidates = cmd.find_candidates(&GitWorktree::new().unwrap(), None).unwrap();
+
+        assert!(!candidates.iter().any(|c| c.worktree.branch == "active"));
+        assert!(candidates.iter().any(|c| c.worktree.branch == "dirty"));
+    }
+
+    #[test]
+    fn test_clean_command_reports_skipped_worktrees() {
+        // What: 構造化出力で、削除したworktreeとスキップしたworktreeが記録されるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let cmd = CleanCommand::new(true, None, None, true, false, false);
+
+        let (skipped, removed): (Vec<_>, Vec<_>) = cmd
+            .find_candidates(&GitWorktree::new().unwrap(), None)
+            .unwrap()
+            .into_iter()
+            .partition(|candidate| candidate.skip_reason.is_some());
+
+        let change = skipped[0].skipped_change();
+        assert_eq!(change.action, ChangeAction::Skipped);
+        assert_eq!(change.reason.as_deref(), Some("uncommitted changes"));
+        assert_eq!(removed[0].change().action, ChangeAction::Removed);
+    }
+
+    #[test]
+    fn test_clean_command_uses_configured_base_branch() {
+        // What: --into省略時は設定のdefaults.base_branchがベースブランチになるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        fs::write(temp_dir.path().join(".gardener.yml"), "defaults:\n  base_branch: develop\n").unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = CleanCommand::new(true, None, None, true, false, false).execute();
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::BranchNotFound { name } if name == "develop"));
+    }
+
+    #[test]
+    fn test_clean_command_unknown_base_branch() {
+        // What: --intoに存在しないブランチを指定するとエラーになるかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = CleanCommand::new(true, Some("develop".to_string()), None, true, false, false).execute();
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::BranchNotFound { .. }));
+    }
+
+    #[test]
+    fn test_clean_command_stale_ignores_base_branch() {
+        // What: --staleだけの場合は、ベースブランチが存在しなくてもエラーにならないかテスト
+        let temp_dir = setup_git_repo_with_worktrees();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = CleanCommand::new(false, Some("develop".to_string()), Some(30), true, false, false);
+
+        assert!(cmd.find_candidates(&GitWorktree::new().unwrap(), None).unwrap().is_empty());
+    }
+}
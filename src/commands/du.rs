@@ -0,0 +1,318 @@
+use crate::disk_usage::{self, format_size, DirUsage, IgnoredMode, Usage, WorktreeUsage};
+use crate::error::{GitGardenerError, Result};
+use crate::git::status::collect_parallel;
+use crate::git::{GitWorktree, WorktreeInfo};
+use crate::output::{self, OutputFormat, FORMAT_VERSION};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub struct DuCommand {
+    // 空の場合はすべてのworktree
+    pub worktrees: Vec<String>,
+    pub ignored: IgnoredMode,
+    pub depth: usize,
+    pub format: OutputFormat,
+}
+
+// 1つのworktreeの使用量（sizeは--ignoredの指定に応じて数えた量）
+#[derive(Debug, Clone, Serialize)]
+struct DuRecord {
+    name: String,
+    branch: Option<String>,
+    path: PathBuf,
+    size: u64,
+    #[serde(flatten)]
+    usage: Usage,
+    entries: Vec<DirUsage>,
+}
+
+#[derive(Serialize)]
+struct DuReport<'a> {
+    version: u32,
+    ignored: IgnoredMode,
+    worktrees: &'a [DuRecord],
+    total: Total,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct Total {
+    size: u64,
+    #[serde(flatten)]
+    usage: Usage,
+}
+
+impl DuCommand {
+    pub fn new(worktrees: Vec<String>, ignored: IgnoredMode, depth: usize) -> Self {
+        Self {
+            worktrees,
+            ignored,
+            depth,
+            format: OutputFormat::Text,
+        }
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let all = git_worktree.list_worktrees()?;
+        let selected = self.select(&all)?;
+
+        // メインworktreeの中にある.gardener/などの別のworktreeは、それぞれの行で数える
+        let skip: Arc<Vec<PathBuf>> = Arc::new(all.iter().map(|w| w.path.clone()).collect());
+        let depth = self.depth;
+        let paths: Vec<PathBuf> = selected.iter().map(|w| w.path.clone()).collect();
+        let measured = collect_parallel(&paths, None, move |path| Some(disk_usage::measure(path, depth, &skip)));
+        let mut usages: Vec<WorktreeUsage> = measured
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+        // worktree間で共有されたデータは、一覧で先に並ぶworktreeに数える
+        disk_usage::charge_shared(&mut usages);
+
+        let mut records: Vec<DuRecord> = selected
+            .into_iter()
+            .zip(usages)
+            .map(|(worktree, usage)| self.record(worktree, usage))
+            .collect();
+        records.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+        print!("{}", format_du(self.format, self.ignored, &records)?);
+        Ok(())
+    }
+
+    // 名前の指定がなければ、ディレクトリが存在するworktreeすべて
+    fn select(&self, all: &[WorktreeInfo]) -> Result<Vec<WorktreeInfo>> {
+        if self.worktrees.is_empty() {
+            return Ok(all.iter().filter(|w| !w.is_prunable && w.path.exists()).cloned().collect());
+        }
+        self.worktrees
+            .iter()
+            .map(|name| {
+                all.iter()
+                    .find(|w| &w.name == name || &w.branch == name)
+                    .cloned()
+                    .ok_or_else(|| GitGardenerError::WorktreeNotFound { name: name.clone() })
+            })
+            .collect()
+    }
+
+    fn record(&self, worktree: WorktreeInfo, usage: WorktreeUsage) -> DuRecord {
+        DuRecord {
+            size: usage.usage.counted(self.ignored),
+            branch: (!worktree.is_detached).then_some(worktree.branch),
+            name: worktree.name,
+            path: worktree.path,
+            usage: usage.usage,
+            entries: usage.entries,
+        }
+    }
+}
+
+fn total(ignored: IgnoredMode, records: &[DuRecord]) -> Total {
+    let mut usage = Usage::default();
+    for record in records {
+        usage.add(record.usage);
+    }
+    Total {
+        size: usage.counted(ignored),
+        usage,
+    }
+}
+
+fn format_du(format: OutputFormat, ignored: IgnoredMode, records: &[DuRecord]) -> Result<String> {
+    let total = total(ignored, records);
+    let report = DuReport {
+        version: FORMAT_VERSION,
+        ignored,
+        worktrees: records,
+        total,
+    };
+
+    match format {
+        OutputFormat::Text => Ok(format_table(ignored, records, total)),
+        OutputFormat::Json => output::to_json(&report),
+        OutputFormat::Yaml => output::to_yaml(&report),
+        // worktreeの行はdepth 0、内訳の行はそのディレクトリのパス
+        OutputFormat::Tsv => Ok(output::format_tsv(
+            &["name", "branch", "path", "depth", "size", "bytes", "ignored_bytes"],
+            records.iter().flat_map(|record| {
+                let row = |path: PathBuf, depth: usize, usage: Usage| {
+                    vec![
+                        record.name.clone(),
+                        record.branch.clone().unwrap_or_default(),
+                        path.display().to_string(),
+                        depth.to_string(),
+                        usage.counted(ignored).to_string(),
+                        usage.bytes.to_string(),
+                        usage.ignored_bytes.to_string(),
+                    ]
+                };
+                std::iter::once(row(record.path.clone(), 0, record.usage)).chain(
+                    record
+                        .entries
+                        .iter()
+                        .map(move |entry| row(record.path.join(&entry.path), entry.depth, entry.usage)),
+                )
+            }),
+        )),
+        OutputFormat::Porcelain => {
+            let mut output = output::porcelain_header();
+            for record in records {
+                output.push_str(&format!("worktree {}\n", output::escape(&record.path.display().to_string())));
+                output.push_str(&format!("name {}\n", output::escape(&record.name)));
+                if let Some(branch) = &record.branch {
+                    output.push_str(&format!("branch {}\n", output::escape(branch)));
+                }
+                output.push_str(&format!("size {}\n", record.size));
+                output.push_str(&format!("bytes {}\n", record.usage.bytes));
+                output.push_str(&format!("ignored {}\n", record.usage.ignored_bytes));
+                for entry in &record.entries {
+                    output.push_str(&format!(
+                        "entry {} {} {}\n",
+                        entry.depth,
+                        entry.usage.counted(ignored),
+                        output::escape(&entry.path.display().to_string())
+                    ));
+                }
+                output.push('\n');
+            }
+            Ok(output)
+        }
+    }
+}
+
+// SIZE・IGNOREDは右寄せ。--depthの内訳はworktreeの下に字下げして並べる
+fn format_table(ignored: IgnoredMode, records: &[DuRecord], total: Total) -> String {
+    if records.is_empty() {
+        return "No worktrees found.\n".to_string();
+    }
+
+    let size_header = match ignored {
+        IgnoredMode::Include => "SIZE",
+        IgnoredMode::Exclude => "TRACKED",
+        IgnoredMode::Only => "IGNORED",
+    };
+    let show_ignored = ignored == IgnoredMode::Include;
+
+    // (サイズ, 無視された分, 名前, パス)
+    let mut rows: Vec<(String, String, String, String)> = Vec::new();
+    for record in records {
+        rows.push((
+            format_size(record.size),
+            format_size(record.usage.ignored_bytes),
+            record.name.clone(),
+            record.path.display().to_string(),
+        ));
+        for entry in &record.entries {
+            rows.push((
+                format_size(entry.usage.counted(ignored)),
+                format_size(entry.usage.ignored_bytes),
+                format!("{}{}/", "  ".repeat(entry.depth), entry.path.display()),
+                String::new(),
+            ));
+        }
+    }
+    let worktree_count = records.len();
+    let total_label = format!("total ({} worktree{})", worktree_count, if worktree_count == 1 { "" } else { "s" });
+
+    let size_width = rows.iter().map(|row| row.0.len()).chain([size_header.len(), format_size(total.size).len()]).max().unwrap_or(0);
+    let ignored_width = rows.iter().map(|row| row.1.len()).chain(["IGNORED".len()]).max().unwrap_or(0);
+    let name_width = rows.iter().map(|row| row.2.chars().count()).chain(["WORKTREE".len(), total_label.len()]).max().unwrap_or(0);
+
+    let line = |size: &str, ignored_size: &str, name: &str, path: &str| {
+        let mut line = format!("{:>width$}  ", size, width = size_width);
+        if show_ignored {
+            line.push_str(&format!("{:>width$}  ", ignored_size, width = ignored_width));
+        }
+        line.push_str(&format!("{:<width$}  {}", name, path, width = name_width));
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut output = line(size_header, "IGNORED", "WORKTREE", "PATH");
+    for (size, ignored_size, name, path) in &rows {
+        output.push_str(&line(size, ignored_size, name, path));
+    }
+    output.push_str(&line(
+        &format_size(total.size),
+        &format_size(total.usage.ignored_bytes),
+        &total_label,
+        "",
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::setup_git_repo;
+    use std::fs;
+
+    fn record(name: &str, bytes: u64, ignored_bytes: u64, entries: Vec<DirUsage>) -> DuRecord {
+        let usage = Usage { bytes, ignored_bytes };
+        DuRecord {
+            name: name.to_string(),
+            branch: Some(name.to_string()),
+            path: PathBuf::from(format!("/repo/{}", name)),
+            size: usage.counted(IgnoredMode::Include),
+            usage,
+            entries,
+        }
+    }
+
+    #[test]
+    fn test_format_du_table_and_structured() {
+        // What: 使用量が表・TSV・porcelain・JSONで合計付きで出力されるかテスト
+        let entries = vec![DirUsage {
+            path: PathBuf::from("target"),
+            depth: 1,
+            usage: Usage { bytes: 2048, ignored_bytes: 2048 },
+        }];
+        let records = vec![record("main", 4096, 2048, entries), record("docs", 512, 0, Vec::new())];
+
+        let table = format_du(OutputFormat::Text, IgnoredMode::Include, &records).unwrap();
+        let excluded = format_du(OutputFormat::Text, IgnoredMode::Exclude, &records).unwrap();
+        let tsv = format_du(OutputFormat::Tsv, IgnoredMode::Include, &records).unwrap();
+        let porcelain = format_du(OutputFormat::Porcelain, IgnoredMode::Include, &records[1..]).unwrap();
+        let json = format_du(OutputFormat::Json, IgnoredMode::Only, &records).unwrap();
+
+        assert_eq!(
+            table,
+            "SIZE  IGNORED  WORKTREE             PATH\n\
+             4.0K     2.0K  main                 /repo/main\n\
+             2.0K     2.0K    target/\n\
+             512B       0B  docs                 /repo/docs\n\
+             4.5K     2.0K  total (2 worktrees)\n"
+        );
+        assert!(excluded.starts_with("TRACKED  WORKTREE"));
+        assert!(excluded.contains("   2.5K  total (2 worktrees)"));
+        assert_eq!(
+            tsv,
+            "name\tbranch\tpath\tdepth\tsize\tbytes\tignored_bytes\n\
+             main\tmain\t/repo/main\t0\t4096\t4096\t2048\n\
+             main\tmain\t/repo/main/target\t1\t2048\t2048\t2048\n\
+             docs\tdocs\t/repo/docs\t0\t512\t512\t0\n"
+        );
+        assert_eq!(porcelain, "version 1\n\nworktree /repo/docs\nname docs\nbranch docs\nsize 512\nbytes 512\nignored 0\n\n");
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["ignored"], "only");
+        assert_eq!(value["total"]["size"], 2048);
+        assert_eq!(value["worktrees"][0]["entries"][0]["ignored_bytes"], 2048);
+    }
+
+    #[test]
+    fn test_du_command_selects_worktrees() {
+        // What: 名前を指定しない場合はすべてのworktree、存在しない名前はエラーになるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        fs::write(repo_path.join("file.txt"), "content").unwrap();
+        std::env::set_current_dir(repo_path).unwrap();
+
+        assert!(DuCommand::new(Vec::new(), IgnoredMode::Include, 1).execute().is_ok());
+        assert!(DuCommand::new(vec!["missing".to_string()], IgnoredMode::Include, 0).execute().is_err());
+    }
+}
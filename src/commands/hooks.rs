@@ -0,0 +1,147 @@
+use crate::error::Result;
+use crate::git::GitWorktree;
+use crate::state::{HookHistory, HookRun, HookRunStatus};
+use crate::time;
+use colored::*;
+
+pub struct HooksHistoryCommand {
+    pub worktree: Option<String>,
+    pub limit: usize,
+}
+
+impl HooksHistoryCommand {
+    pub fn new(worktree: Option<String>, limit: usize) -> Self {
+        Self { worktree, limit }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let history = HookHistory::load(&git_worktree.get_state_dir())?;
+
+        let runs = self.select_runs(&history.runs);
+        if runs.is_empty() {
+            println!("No hook history found.");
+            return Ok(());
+        }
+
+        println!(
+            "{:<20} {:<25} {:>9} {:<9} {:<40} {}",
+            "STARTED".blue().bold(),
+            "WORKTREE".blue().bold(),
+            "DURATION".blue().bold(),
+            "STATUS".blue().bold(),
+            "HOOK".blue().bold(),
+            "LOG".blue().bold()
+        );
+        println!("{}", "-".repeat(120).bright_black());
+
+        for run in runs {
+            let status = match run.status {
+                HookRunStatus::Success => run.status.to_string().green(),
+                HookRunStatus::Failed => match run.exit_code {
+                    Some(code) => format!("failed({})", code).red(),
+                    None => run.status.to_string().red(),
+                },
+                HookRunStatus::Skipped | HookRunStatus::Restored => run.status.to_string().cyan(),
+                HookRunStatus::Interrupted => run.status.to_string().yellow(),
+            };
+            let log = run
+                .log_path
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+
+            println!(
+                "{:<20} {:<25} {:>9} {:<9} {:<40} {}",
+                time::format_timestamp(run.started_at),
+                run.worktree.green(),
+                time::format_duration(run.duration_ms),
+                status,
+                run.hook,
+                log.bright_black()
+            );
+        }
+
+        Ok(())
+    }
+
+    // 新しい順に、指定されたworktree（ブランチ名またはディレクトリ名）の履歴を選ぶ
+    fn select_runs<'a>(&self, runs: &'a [HookRun]) -> Vec<&'a HookRun> {
+        runs.iter()
+            .rev()
+            .filter(|run| match &self.worktree {
+                Some(worktree) => {
+                    run.worktree == *worktree
+                        || run.worktree_path.file_name().is_some_and(|name| name == worktree.as_str())
+                }
+                None => true,
+            })
+            .take(self.limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, setup_git_repo};
+    use std::path::PathBuf;
+
+    fn hook_run(worktree: &str, hook: &str) -> HookRun {
+        HookRun {
+            worktree: worktree.to_string(),
+            worktree_path: PathBuf::from(format!("/repo/.gardener/{}", worktree)),
+            hook: hook.to_string(),
+            started_at: 1_700_000_000,
+            duration_ms: 100,
+            status: HookRunStatus::Success,
+            exit_code: None,
+            log_path: None,
+        }
+    }
+
+    #[test]
+    fn test_select_runs_filters_by_worktree_newest_first() {
+        // What: worktreeで絞り込み、新しい順に件数制限付きで返すかテスト
+        let runs = vec![
+            hook_run("feature-a", "first"),
+            hook_run("feature-b", "other"),
+            hook_run("feature-a", "second"),
+            hook_run("feature-a", "third"),
+        ];
+
+        let cmd = HooksHistoryCommand::new(Some("feature-a".to_string()), 2);
+        let selected = cmd.select_runs(&runs);
+
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].hook, "third");
+        assert_eq!(selected[1].hook, "second");
+    }
+
+    #[test]
+    fn test_select_runs_without_filter_returns_all() {
+        // What: worktree指定なしの場合は全worktreeの履歴を返すかテスト
+        let runs = vec![hook_run("feature-a", "first"), hook_run("feature-b", "other")];
+
+        let cmd = HooksHistoryCommand::new(None, 20);
+
+        assert_eq!(cmd.select_runs(&runs).len(), 2);
+    }
+
+    #[test]
+    fn test_history_is_shared_with_linked_worktree() {
+        // What: リンクされたworktree内からもメインと同じ履歴を参照するかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        add_worktree(repo_path, "linked", "linked");
+        let main_state_dir = GitWorktree::from_path(repo_path).unwrap().get_state_dir();
+        HookHistory::append(&main_state_dir, hook_run("feature-a", "first")).unwrap();
+        std::env::set_current_dir(repo_path.join("linked")).unwrap();
+        
+        let state_dir = GitWorktree::new().unwrap().get_state_dir();
+        
+        assert_eq!(state_dir.canonicalize().unwrap(), main_state_dir.canonicalize().unwrap());
+        assert_eq!(HookHistory::load(&state_dir).unwrap().runs.len(), 1);
+        assert!(HooksHistoryCommand::new(None, 20).execute().is_ok());
+    }
+}
@@ -1,119 +1,969 @@
-use crate::error::Result;
-use crate::git::GitWorktree;
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::status::WorktreeStatus;
+use crate::git::status_cache::StatusCache;
+use crate::git::status::GitStatus;
+use crate::git::{load_statuses, CommitSummary, GitWorktree, WorktreeInfo};
+use crate::output::{self, OutputFormat, FORMAT_VERSION};
+use crate::template::{Template, Value};
+use crate::time;
+use clap::ValueEnum;
 use colored::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// 表の列（`--columns`や設定のdefaults.list_columnsで選ぶ）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Column {
+    // @はメインworktree、*は現在のworktree
+    Current,
+    Name,
+    Branch,
+    Status,
+    AheadBehind,
+    Age,
+    Sha,
+    Subject,
+    Flags,
+    Path,
+}
+
+const ALL_COLUMNS: [Column; 10] = [
+    Column::Current,
+    Column::Name,
+    Column::Branch,
+    Column::Status,
+    Column::AheadBehind,
+    Column::Age,
+    Column::Sha,
+    Column::Subject,
+    Column::Flags,
+    Column::Path,
+];
+
+const DEFAULT_COLUMNS: [Column; 7] = [
+    Column::Current,
+    Column::Branch,
+    Column::Status,
+    Column::AheadBehind,
+    Column::Age,
+    Column::Path,
+    Column::Flags,
+];
+
+// 件名が長い場合はここで切り詰める
+const MAX_SUBJECT_WIDTH: usize = 50;
+
+// `--format`のテンプレートで使えるフィールド
+const TEMPLATE_FIELDS: [&str; 30] = [
+    "name",
+    "branch",
+    "path",
+    "head",
+    "sha",
+    "subject",
+    "age",
+    "time",
+    "main",
+    "current",
+    "detached",
+    "locked",
+    "lock_reason",
+    "prunable",
+    "status",
+    "status.dirty",
+    "status.staged",
+    "status.unstaged",
+    "status.untracked",
+    "status.conflicted",
+    "status.renamed",
+    "status.deleted",
+    "status.stash",
+    "status.operation",
+    "status.upstream",
+    "status.ahead",
+    "status.behind",
+    "status.base",
+    "status.base.ahead",
+    "status.base.behind",
+];
+
+// `--format`の値（組み込みの形式、設定のformatsにある名前、またはテンプレートそのもの）
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListFormat {
+    Builtin(OutputFormat),
+    Template(String),
+}
+
+impl ListFormat {
+    pub fn parse(format: &str) -> Self {
+        match OutputFormat::from_str(format, true) {
+            Ok(format) => ListFormat::Builtin(format),
+            Err(_) => ListFormat::Template(format.to_string()),
+        }
+    }
+    
+    // 設定の名前付きテンプレートを優先し、`{`を含む場合はテンプレートとして読む
+    fn template(name: &str, formats: Option<&BTreeMap<String, String>>) -> Result<Template> {
+        if let Some(template) = formats.and_then(|formats| formats.get(name)) {
+            return Template::parse(template, &TEMPLATE_FIELDS);
+        }
+        if name.contains('{') {
+            return Template::parse(name, &TEMPLATE_FIELDS);
+        }
+        Err(GitGardenerError::UnknownFormat {
+            name: name.to_string(),
+            available: formats
+                .filter(|formats| !formats.is_empty())
+                .map(|formats| formats.keys().cloned().collect::<Vec<_>>().join(", "))
+                .unwrap_or_else(|| "none defined".to_string()),
+        })
+    }
+}
+
+impl Column {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Column::Current => "current",
+            Column::Name => "name",
+            Column::Branch => "branch",
+            Column::Status => "status",
+            Column::AheadBehind => "ahead-behind",
+            Column::Age => "age",
+            Column::Sha => "sha",
+            Column::Subject => "subject",
+            Column::Flags => "flags",
+            Column::Path => "path",
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Current => "",
+            Column::Name => "NAME",
+            Column::Branch => "BRANCH",
+            Column::Status => "STATUS",
+            Column::AheadBehind => "UPSTREAM",
+            Column::Age => "AGE",
+            Column::Sha => "SHA",
+            Column::Subject => "SUBJECT",
+            Column::Flags => "FLAGS",
+            Column::Path => "PATH",
+        }
+    }
+
+    // `branch,status`のようなカンマ区切りも受け付ける
+    pub fn parse_list(names: &[String]) -> Result<Vec<Column>> {
+        names
+            .iter()
+            .flat_map(|name| name.split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                ALL_COLUMNS
+                    .iter()
+                    .copied()
+                    .find(|column| column.key() == name)
+                    .ok_or_else(|| GitGardenerError::UnknownColumn {
+                        name: name.to_string(),
+                        available: ALL_COLUMNS.iter().map(Column::key).collect::<Vec<_>>().join(", "),
+                    })
+            })
+            .collect()
+    }
+
+    fn needs_status(&self) -> bool {
+        matches!(self, Column::Status | Column::AheadBehind)
+    }
+
+    fn needs_commit(&self) -> bool {
+        matches!(self, Column::Age | Column::Sha | Column::Subject)
+    }
+
+    fn value(&self, worktree: &WorktreeInfo, commit: Option<&CommitSummary>, now: i64) -> String {
+        match self {
+            Column::Current => marker(worktree),
+            Column::Name => worktree.name.clone(),
+            Column::Branch => worktree.branch.clone(),
+            Column::Status => match worktree.status.as_ref().map(|status| &status.working_tree_status) {
+                Some(WorktreeStatus::Dirty) => "dirty".to_string(),
+                Some(_) => "clean".to_string(),
+                None => "?".to_string(),
+            },
+            Column::AheadBehind => match &worktree.status {
+                Some(status) if status.upstream.is_some() => {
+                    format!("↑{}↓{}", status.ahead_count, status.behind_count)
+                }
+                Some(_) => "-".to_string(),
+                None => "?".to_string(),
+            },
+            Column::Age => commit.map(|c| time::format_age(now - c.time)).unwrap_or_default(),
+            Column::Sha => commit.map(|c| c.short_id.clone()).unwrap_or_default(),
+            Column::Subject => commit.map(|c| truncate(&c.subject, MAX_SUBJECT_WIDTH)).unwrap_or_default(),
+            Column::Flags => lock_label(worktree),
+            Column::Path => worktree.path.display().to_string(),
+        }
+    }
+
+    fn paint(&self, text: String, worktree: &WorktreeInfo) -> ColoredString {
+        match self {
+            Column::Branch | Column::Name => text.green(),
+            Column::Path => text.yellow(),
+            Column::Status => match worktree.status.as_ref().map(|status| &status.working_tree_status) {
+                Some(WorktreeStatus::Dirty) => text.red(),
+                Some(_) => text.normal(),
+                None => text.bright_black(),
+            },
+            Column::AheadBehind => match &worktree.status {
+                Some(status) if status.ahead_count > 0 || status.behind_count > 0 => text.cyan(),
+                _ => text.bright_black(),
+            },
+            Column::Age | Column::Sha => text.bright_black(),
+            Column::Flags => text.red(),
+            Column::Current | Column::Subject => text.normal(),
+        }
+    }
+}
+
+// 構造化出力での1つのworktree（WorktreeInfoとステータス）
+#[derive(Debug, Serialize)]
+struct WorktreeRecord<'a> {
+    name: &'a str,
+    path: &'a PathBuf,
+    // detached HEADの場合はNone
+    branch: Option<&'a str>,
+    head: Option<String>,
+    is_main: bool,
+    is_current: bool,
+    is_detached: bool,
+    is_locked: bool,
+    lock_reason: Option<&'a str>,
+    is_prunable: bool,
+    // 取得できなかった場合（タイムアウトなど）はNone
+    status: Option<&'a GitStatus>,
+}
+
+impl<'a> From<&'a WorktreeInfo> for WorktreeRecord<'a> {
+    fn from(worktree: &'a WorktreeInfo) -> Self {
+        Self {
+            name: &worktree.name,
+            path: &worktree.path,
+            branch: (!worktree.is_detached).then_some(worktree.branch.as_str()),
+            head: worktree.head.map(|oid| oid.to_string()),
+            is_main: worktree.is_main,
+            is_current: worktree.is_current,
+            is_detached: worktree.is_detached,
+            is_locked: worktree.is_locked,
+            lock_reason: worktree.lock_reason.as_deref(),
+            is_prunable: worktree.is_prunable,
+            status: worktree.status.as_ref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ListReport<'a> {
+    version: u32,
+    worktrees: Vec<WorktreeRecord<'a>>,
+}
+
+// 一覧の並べ替え
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Branch,
+    // 最後のコミットが新しい順
+    Age,
+    Path,
+    // 変更のあるものから（dirty, diverged, ahead, behind, clean, 取得できなかったもの）
+    Status,
+}
+
+// 一覧の絞り込み（指定した条件をすべて満たすworktreeだけを残す）
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    pub dirty: bool,
+    pub clean: bool,
+    pub merged: bool,
+    pub stale: Option<u32>,
+    pub locked: bool,
+    // ブランチ名またはworktree名に対するglob（`feature/*`など）
+    pub pattern: Option<String>,
+}
+
+impl ListFilter {
+    fn is_empty(&self) -> bool {
+        !self.dirty && !self.clean && !self.merged && self.stale.is_none() && !self.locked && self.pattern.is_none()
+    }
+    
+    fn needs_status(&self) -> bool {
+        self.dirty || self.clean
+    }
+    
+    // ステータスが取得できなかったworktreeは--dirtyにも--cleanにも含めない
+    fn retain(&self, git_worktree: &GitWorktree, base_branch: Option<&str>, worktrees: &mut Vec<WorktreeInfo>) -> Result<()> {
+        let pathspec = self
+            .pattern
+            .as_ref()
+            .map(|pattern| git2::Pathspec::new([pattern.as_str()]))
+            .transpose()?;
+        
+        let mut kept = Vec::new();
+        for worktree in worktrees.drain(..) {
+            let is_dirty = worktree.status.as_ref().map(|status| status.working_tree_status == WorktreeStatus::Dirty);
+            if (self.dirty && is_dirty != Some(true)) || (self.clean && is_dirty != Some(false)) {
+                continue;
+            }
+            if self.locked && !worktree.is_locked {
+                continue;
+            }
+            if let Some(pathspec) = &pathspec {
+                let matches = |name: &str| pathspec.matches_path(std::path::Path::new(name), git2::PathspecFlags::DEFAULT);
+                if !matches(&worktree.name) && (worktree.is_detached || !matches(&worktree.branch)) {
+                    continue;
+                }
+            }
+            // マージ済み・放置の判定はブランチのあるworktreeだけが対象
+            if self.merged {
+                let merged = match base_branch {
+                    // ベースブランチ自身はマージ済みとして扱わない
+                    Some(base) if !worktree.is_detached && worktree.branch != base => {
+                        git_worktree.is_branch_merged(&worktree.branch, base)?
+                    }
+                    _ => false,
+                };
+                if !merged {
+                    continue;
+                }
+            }
+            if let Some(days) = self.stale {
+                if worktree.is_detached || !git_worktree.is_worktree_stale(&worktree, days) {
+                    continue;
+                }
+            }
+            kept.push(worktree);
+        }
+        *worktrees = kept;
+        Ok(())
+    }
+}
 
 pub struct ListCommand {
     pub names_only: bool,
+    pub format: ListFormat,
+    pub refresh: bool,
+    pub columns: Option<Vec<String>>,
+    pub filter: ListFilter,
+    pub sort: Option<SortKey>,
+    pub tree: bool,
 }
 
 impl ListCommand {
-    pub fn new(names_only: bool) -> Self {
-        Self { names_only }
+    pub fn new(names_only: bool, format: ListFormat, refresh: bool, columns: Option<Vec<String>>) -> Self {
+        Self {
+            names_only,
+            format,
+            refresh,
+            columns,
+            filter: ListFilter::default(),
+            sort: None,
+            tree: false,
+        }
+    }
+    
+    pub fn with_filter(mut self, filter: ListFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+    
+    pub fn with_sort(mut self, sort: Option<SortKey>) -> Self {
+        self.sort = sort;
+        self
+    }
+    
+    pub fn with_tree(mut self, tree: bool) -> Self {
+        self.tree = tree;
+        self
     }
     
     pub fn execute(&self) -> Result<()> {
         let git_worktree = GitWorktree::new()?;
-        let worktrees = git_worktree.list_worktrees()?;
+        let config = Config::load_for_repo(&git_worktree.get_repository_root()?)?;
+        let mut worktrees = git_worktree.list_worktrees()?;
         
-        if worktrees.is_empty() {
-            if !self.names_only {
-                println!("No worktrees found.");
+        let template = match &self.format {
+            ListFormat::Template(name) => Some(ListFormat::template(name, config.formats.as_ref())?),
+            ListFormat::Builtin(_) => None,
+        };
+        let structured = match self.format {
+            ListFormat::Builtin(format) if format.is_structured() => Some(format),
+            _ => None,
+        };
+        // 列は--columns、設定、既定の順に決める（表を出力する場合のみ）
+        let columns = if self.names_only || self.tree || template.is_some() || structured.is_some() {
+            Vec::new()
+        } else {
+            match self.columns.as_ref().or(config.defaults.list_columns.as_ref()) {
+                Some(names) => Column::parse_list(names)?,
+                None => DEFAULT_COLUMNS.to_vec(),
+            }
+        };
+        let template_fields = template.as_ref().map(Template::fields).unwrap_or_default();
+        
+        // ステータスとコミットは、出力・絞り込み・並べ替えに必要な場合だけ取得する
+        let needs_status = structured.is_some()
+            || self.tree
+            || self.filter.needs_status()
+            || self.sort == Some(SortKey::Status)
+            || columns.iter().any(Column::needs_status)
+            || template_fields.iter().any(|field| field.starts_with("status"));
+        let needs_commit = self.sort == Some(SortKey::Age)
+            || columns.iter().any(Column::needs_commit)
+            || template_fields.iter().any(|field| matches!(*field, "sha" | "subject" | "age" | "time"));
+        
+        if needs_status {
+            self.load_statuses(&git_worktree, &config, &mut worktrees)?;
+        }
+        let base_branch = config.defaults.base_branch.clone().or_else(|| {
+            worktrees.iter().find(|w| w.is_main && !w.is_detached).map(|w| w.branch.clone())
+        });
+        self.filter.retain(&git_worktree, base_branch.as_deref(), &mut worktrees)?;
+        
+        let mut rows: Vec<(WorktreeInfo, Option<CommitSummary>)> = worktrees
+            .into_iter()
+            .map(|worktree| {
+                let commit = worktree.head.filter(|_| needs_commit).and_then(|oid| git_worktree.commit_summary(oid));
+                (worktree, commit)
+            })
+            .collect();
+        if let Some(key) = self.sort {
+            sort_rows(&mut rows, key);
+        }
+        
+        if let Some(format) = structured {
+            let worktrees: Vec<WorktreeInfo> = rows.into_iter().map(|(worktree, _)| worktree).collect();
+            print!("{}", format_worktrees(format, &worktrees)?);
+            return Ok(());
+        }
+        
+        if let Some(template) = template {
+            let now = time::now_unix();
+            for line in template.render_all(&rows, |(worktree, commit), field| {
+                template_value(worktree, commit.as_ref(), now, field)
+            }) {
+                println!("{}", line);
             }
             return Ok(());
         }
         
         if self.names_only {
-            // Shell completion用にworktree名のみを出力
-            for worktree in worktrees {
-                println!("{}", worktree.branch);
+            // Shell completion用にworktree名のみを出力（detached HEADはworktree名）
+            for (worktree, _) in rows {
+                if worktree.is_detached {
+                    println!("{}", worktree.name);
+                } else {
+                    println!("{}", worktree.branch);
+                }
             }
+            return Ok(());
+        }
+        
+        if rows.is_empty() {
+            if self.filter.is_empty() {
+                println!("No worktrees found.");
+            } else {
+                println!("No worktrees match the filters.");
+            }
+            return Ok(());
+        }
+        
+        let (worktrees, commits): (Vec<WorktreeInfo>, Vec<Option<CommitSummary>>) = rows.into_iter().unzip();
+        if self.tree {
+            print!("{}", format_tree(&worktrees));
+            return Ok(());
+        }
+        print!("{}", format_table(&columns, &worktrees, &commits, time::now_unix()));
+        Ok(())
+    }
+    
+    // キャッシュを使って並列に取得する
+    // --dirty/--cleanで絞り込む場合は、時間内に取得できなかったworktreeが黙って除かれないようタイムアウトしない
+    fn load_statuses(&self, git_worktree: &GitWorktree, config: &Config, worktrees: &mut [WorktreeInfo]) -> Result<()> {
+        let mut cache = StatusCache::load(&git_worktree.get_state_dir())?
+            .refresh(self.refresh)
+            .wait(self.filter.needs_status());
+        load_statuses(
+            worktrees,
+            &config.defaults.status_query(),
+            config.defaults.status_timeout(),
+            Some(&mut cache),
+        );
+        cache.save()
+    }
+}
+
+// 同じ値の間では元の順（メインworktreeが先頭）を保つ
+fn sort_rows(rows: &mut [(WorktreeInfo, Option<CommitSummary>)], key: SortKey) {
+    match key {
+        SortKey::Branch => rows.sort_by(|(a, _), (b, _)| a.branch.cmp(&b.branch)),
+        SortKey::Path => rows.sort_by(|(a, _), (b, _)| a.path.cmp(&b.path)),
+        SortKey::Age => rows.sort_by_key(|(_, commit)| std::cmp::Reverse(commit.as_ref().map(|c| c.time))),
+        SortKey::Status => rows.sort_by_key(|(worktree, _)| match worktree.status.as_ref().map(|s| &s.working_tree_status) {
+            Some(WorktreeStatus::Dirty) => 0,
+            Some(WorktreeStatus::Diverged) => 1,
+            Some(WorktreeStatus::Ahead) => 2,
+            Some(WorktreeStatus::Behind) => 3,
+            Some(WorktreeStatus::Clean) => 4,
+            None => 5,
+        }),
+    }
+}
+
+// テンプレートのフィールドの値（ステータス・コミットが取得できなかった場合は空）
+fn template_value(worktree: &WorktreeInfo, commit: Option<&CommitSummary>, now: i64, field: &str) -> Value {
+    let status = worktree.status.as_ref();
+    match field {
+        "name" => worktree.name.clone().into(),
+        "branch" => (!worktree.is_detached).then(|| worktree.branch.clone()).into(),
+        "path" => worktree.path.display().to_string().into(),
+        "head" => worktree.head.map(|oid| oid.to_string()).into(),
+        "sha" => commit.map(|c| c.short_id.clone()).into(),
+        "subject" => commit.map(|c| c.subject.clone()).into(),
+        "age" => commit.map(|c| time::format_age(now - c.time)).into(),
+        "time" => commit.map_or(Value::Missing, |c| Value::Number(c.time)),
+        "main" => worktree.is_main.into(),
+        "current" => worktree.is_current.into(),
+        "detached" => worktree.is_detached.into(),
+        "locked" => worktree.is_locked.into(),
+        "lock_reason" => worktree.lock_reason.clone().into(),
+        "prunable" => worktree.is_prunable.into(),
+        "status" => status.map(|s| status_key(&s.working_tree_status).to_string()).into(),
+        "status.dirty" => status.map(|s| s.working_tree_status == WorktreeStatus::Dirty).into(),
+        "status.staged" => status.map(|s| s.has_staged_changes).into(),
+        "status.unstaged" => status.map(|s| s.has_unstaged_changes).into(),
+        "status.untracked" => status.map(|s| s.untracked_count).into(),
+        "status.conflicted" => status.map(|s| s.conflicted_count).into(),
+        "status.renamed" => status.map(|s| s.renamed_count).into(),
+        "status.deleted" => status.map(|s| s.deleted_count).into(),
+        "status.stash" => status.map(|s| s.stash_count).into(),
+        "status.operation" => status.and_then(|s| s.operation).map(|op| op.to_string()).into(),
+        "status.upstream" => status.and_then(|s| s.upstream.clone()).into(),
+        "status.ahead" => status.map(|s| s.ahead_count).into(),
+        "status.behind" => status.map(|s| s.behind_count).into(),
+        "status.base" => status.and_then(|s| s.base.as_ref()).map(|b| b.branch.clone()).into(),
+        "status.base.ahead" => status.and_then(|s| s.base.as_ref()).map(|b| b.ahead).into(),
+        "status.base.behind" => status.and_then(|s| s.base.as_ref()).map(|b| b.behind).into(),
+        _ => Value::Missing,
+    }
+}
+
+// 列幅を揃えた表（現在のworktreeの行は太字）
+fn format_table(columns: &[Column], worktrees: &[WorktreeInfo], commits: &[Option<CommitSummary>], now: i64) -> String {
+    let rows: Vec<Vec<String>> = worktrees
+        .iter()
+        .zip(commits)
+        .map(|(worktree, commit)| columns.iter().map(|column| column.value(worktree, commit.as_ref(), now)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.header().len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let pad = |text: &str, i: usize| {
+        // 最後の列は行末に空白を残さない
+        if i + 1 == columns.len() {
+            text.to_string()
         } else {
-            // 通常の表形式表示
-            println!(
-                "{:<30} {:<50}",
-                "BRANCH".blue().bold(),
-                "PATH".blue().bold()
-            );
-            println!("{}", "-".repeat(80).bright_black());
+            format!("{}{}", text, " ".repeat(widths[i] - text.chars().count()))
+        }
+    };
+    
+    let mut output = String::new();
+    let header: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| pad(column.header(), i).blue().bold().to_string())
+        .collect();
+    output.push_str(header.join(" ").trim_end());
+    output.push('\n');
+    let total_width = widths.iter().sum::<usize>() + widths.len().saturating_sub(1);
+    output.push_str(&"-".repeat(total_width).bright_black().to_string());
+    output.push('\n');
+    
+    for (worktree, row) in worktrees.iter().zip(&rows) {
+        let cells: Vec<String> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let cell = column.paint(pad(&row[i], i), worktree);
+                if worktree.is_current { cell.bold() } else { cell }.to_string()
+            })
+            .collect();
+        output.push_str(cells.join(" ").trim_end());
+        output.push('\n');
+    }
+    output
+}
 
-            for worktree in worktrees {
-                println!(
-                    "{:<30} {:<50}",
-                    worktree.branch.green(),
-                    worktree.path.display().to_string().yellow()
-                );
+// --treeでのブランチ名の`/`区切りの階層（`feature/`、`user/alice/`など）
+#[derive(Default)]
+struct TreeGroup<'a> {
+    name: String,
+    entries: Vec<TreeEntry<'a>>,
+}
+
+enum TreeEntry<'a> {
+    // ブランチ名の最後の部分と、そのworktree
+    Worktree(String, &'a WorktreeInfo),
+    Group(TreeGroup<'a>),
+}
+
+// グループ内のworktreeの集計
+#[derive(Debug, Default, PartialEq)]
+struct TreeStats {
+    count: usize,
+    dirty: usize,
+    ahead: u32,
+    behind: u32,
+}
+
+impl<'a> TreeGroup<'a> {
+    // 一覧の順（--sort）を保ったまま、最初に現れた位置にグループを作る
+    fn build(worktrees: &'a [WorktreeInfo]) -> Self {
+        let mut root = TreeGroup::default();
+        for worktree in worktrees {
+            // detached HEADのworktreeはworktree名で並べる
+            let full_name = if worktree.is_detached { &worktree.name } else { &worktree.branch };
+            let mut segments: Vec<&str> = full_name.split('/').collect();
+            let label = segments.pop().unwrap_or_default().to_string();
+            
+            let mut group = &mut root;
+            for segment in segments {
+                let name = format!("{}/", segment);
+                let index = match group.entries.iter().position(|entry| matches!(entry, TreeEntry::Group(g) if g.name == name)) {
+                    Some(index) => index,
+                    None => {
+                        group.entries.push(TreeEntry::Group(TreeGroup { name, entries: Vec::new() }));
+                        group.entries.len() - 1
+                    }
+                };
+                group = match &mut group.entries[index] {
+                    TreeEntry::Group(child) => child,
+                    TreeEntry::Worktree(..) => unreachable!("index points to a group"),
+                };
             }
+            group.entries.push(TreeEntry::Worktree(label, worktree));
         }
         
-        Ok(())
+        for entry in &mut root.entries {
+            if let TreeEntry::Group(group) = entry {
+                group.collapse();
+            }
+        }
+        root
+    }
+    
+    // 子が1つのグループだけのグループはまとめる（`user/` + `alice/` → `user/alice/`）
+    fn collapse(&mut self) {
+        while let [TreeEntry::Group(_)] = self.entries.as_slice() {
+            let Some(TreeEntry::Group(child)) = self.entries.pop() else { unreachable!() };
+            self.name.push_str(&child.name);
+            self.entries = child.entries;
+        }
+        for entry in &mut self.entries {
+            if let TreeEntry::Group(group) = entry {
+                group.collapse();
+            }
+        }
+    }
+    
+    fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats::default();
+        for entry in &self.entries {
+            match entry {
+                TreeEntry::Worktree(_, worktree) => {
+                    stats.count += 1;
+                    if let Some(status) = &worktree.status {
+                        if status.working_tree_status == WorktreeStatus::Dirty {
+                            stats.dirty += 1;
+                        }
+                        stats.ahead += status.ahead_count;
+                        stats.behind += status.behind_count;
+                    }
+                }
+                TreeEntry::Group(group) => {
+                    let child = group.stats();
+                    stats.count += child.count;
+                    stats.dirty += child.dirty;
+                    stats.ahead += child.ahead;
+                    stats.behind += child.behind;
+                }
+            }
+        }
+        stats
+    }
+}
+
+impl TreeStats {
+    // `3 worktrees, 1 dirty, ↑2 ↓1`（0の項目は省く）
+    fn summary(&self) -> String {
+        let mut parts = vec![format!("{} worktree{}", self.count, if self.count == 1 { "" } else { "s" })];
+        if self.dirty > 0 {
+            parts.push(format!("{} dirty", self.dirty));
+        }
+        let arrows: Vec<String> = [("↑", self.ahead), ("↓", self.behind)]
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(arrow, count)| format!("{}{}", arrow, count))
+            .collect();
+        if !arrows.is_empty() {
+            parts.push(arrows.join(" "));
+        }
+        parts.join(", ")
+    }
+}
+
+// 1行分（左側の木の部分は幅を揃えるため、色を付ける前の文字列で持つ）
+enum TreeLine<'a> {
+    Group { tree: String, stats: TreeStats },
+    Worktree { tree: String, worktree: &'a WorktreeInfo },
+}
+
+fn tree_lines<'a>(group: &TreeGroup<'a>, indent: &str, is_root: bool, lines: &mut Vec<TreeLine<'a>>) {
+    for (i, entry) in group.entries.iter().enumerate() {
+        let is_last = i + 1 == group.entries.len();
+        let (branch, continuation) = match (is_root, is_last) {
+            (true, _) => ("", ""),
+            (false, false) => ("├── ", "│   "),
+            (false, true) => ("└── ", "    "),
+        };
+        match entry {
+            TreeEntry::Worktree(label, worktree) => lines.push(TreeLine::Worktree {
+                tree: format!("{}{}{}", indent, branch, label),
+                worktree,
+            }),
+            TreeEntry::Group(child) => {
+                lines.push(TreeLine::Group {
+                    tree: format!("{}{}{}", indent, branch, child.name),
+                    stats: child.stats(),
+                });
+                tree_lines(child, &format!("{}{}", indent, continuation), false, lines);
+            }
+        }
+    }
+}
+
+// ブランチ名の階層ごとにまとめた表示（グループには件数と変更・ahead/behindの合計を付ける）
+fn format_tree(worktrees: &[WorktreeInfo]) -> String {
+    let root = TreeGroup::build(worktrees);
+    let mut lines = Vec::new();
+    tree_lines(&root, "", true, &mut lines);
+    
+    let tree_width = lines
+        .iter()
+        .filter_map(|line| match line {
+            TreeLine::Worktree { tree, .. } => Some(tree.chars().count()),
+            TreeLine::Group { .. } => None,
+        })
+        .max()
+        .unwrap_or(0);
+    let status_width = worktrees.iter().map(|w| status_label(w).chars().count()).max().unwrap_or(0);
+    
+    let mut output = String::new();
+    for line in lines {
+        let text = match line {
+            TreeLine::Group { tree, stats } => {
+                format!("   {} {}", tree.blue().bold(), format!("({})", stats.summary()).bright_black())
+            }
+            TreeLine::Worktree { tree, worktree } => {
+                let status = status_label(worktree);
+                let padded_tree = format!("{}{}", tree, " ".repeat(tree_width - tree.chars().count()));
+                let padded_status = format!("{}{}", status, " ".repeat(status_width - status.chars().count()));
+                let cells = [
+                    marker(worktree).normal(),
+                    padded_tree.green(),
+                    Column::Status.paint(padded_status, worktree),
+                    worktree.path.display().to_string().yellow(),
+                    lock_label(worktree).red(),
+                ];
+                let cells: Vec<String> = cells
+                    .into_iter()
+                    .map(|cell| if worktree.is_current { cell.bold() } else { cell }.to_string())
+                    .collect();
+                cells.join(" ")
+            }
+        };
+        output.push_str(text.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(width - 1).collect();
+    format!("{}…", truncated)
+}
+
+fn marker(worktree: &WorktreeInfo) -> String {
+    format!(
+        "{}{}",
+        if worktree.is_main { "@" } else { " " },
+        if worktree.is_current { "*" } else { " " }
+    )
+}
+
+// ステータス列の表示（取得できなかった場合は?）
+pub(crate) fn status_label(worktree: &WorktreeInfo) -> String {
+    let Some(status) = &worktree.status else {
+        return "?".to_string();
+    };
+    match status.working_tree_status {
+        WorktreeStatus::Clean => "clean".to_string(),
+        WorktreeStatus::Dirty => "dirty".to_string(),
+        WorktreeStatus::Ahead => format!("↑{}", status.ahead_count),
+        WorktreeStatus::Behind => format!("↓{}", status.behind_count),
+        WorktreeStatus::Diverged => format!("↑{}↓{}", status.ahead_count, status.behind_count),
+    }
+}
+
+fn lock_label(worktree: &WorktreeInfo) -> String {
+    let lock = match (&worktree.is_locked, &worktree.lock_reason) {
+        (true, Some(reason)) => format!("[locked: {}]", reason),
+        (true, None) => "[locked]".to_string(),
+        (false, _) => String::new(),
+    };
+    
+    if worktree.is_prunable {
+        format!("{}[prunable]", lock)
+    } else {
+        lock
+    }
+}
+
+fn format_worktrees(format: OutputFormat, worktrees: &[WorktreeInfo]) -> Result<String> {
+    let report = ListReport {
+        version: FORMAT_VERSION,
+        worktrees: worktrees.iter().map(WorktreeRecord::from).collect(),
+    };
+    match format {
+        OutputFormat::Text => Ok(String::new()),
+        OutputFormat::Json => output::to_json(&report),
+        OutputFormat::Yaml => output::to_yaml(&report),
+        OutputFormat::Tsv => Ok(format_tsv(worktrees)),
+        OutputFormat::Porcelain => Ok(format_porcelain(worktrees)),
+    }
+}
+
+fn format_tsv(worktrees: &[WorktreeInfo]) -> String {
+    let header = [
+        "name", "branch", "path", "head", "status", "upstream", "ahead", "behind",
+        "main", "current", "detached", "locked", "prunable",
+    ];
+    output::format_tsv(
+        &header,
+        worktrees.iter().map(|worktree| {
+            let status = worktree.status.as_ref();
+            vec![
+                worktree.name.clone(),
+                if worktree.is_detached { String::new() } else { worktree.branch.clone() },
+                worktree.path.display().to_string(),
+                worktree.head.map(|oid| oid.to_string()).unwrap_or_default(),
+                status.map(|s| status_key(&s.working_tree_status).to_string()).unwrap_or_default(),
+                status.and_then(|s| s.upstream.clone()).unwrap_or_default(),
+                status.map(|s| s.ahead_count.to_string()).unwrap_or_default(),
+                status.map(|s| s.behind_count.to_string()).unwrap_or_default(),
+                worktree.is_main.to_string(),
+                worktree.is_current.to_string(),
+                worktree.is_detached.to_string(),
+                worktree.is_locked.to_string(),
+                worktree.is_prunable.to_string(),
+            ]
+        }),
+    )
+}
+
+fn status_key(status: &WorktreeStatus) -> &'static str {
+    match status {
+        WorktreeStatus::Clean => "clean",
+        WorktreeStatus::Dirty => "dirty",
+        WorktreeStatus::Ahead => "ahead",
+        WorktreeStatus::Behind => "behind",
+        WorktreeStatus::Diverged => "diverged",
     }
 }
 
+// スクリプト向けの出力（`git worktree list --porcelain`と同様に空行区切りのレコード）
+// 先頭の`version N`行で形式のバージョンを示す。行の追加はバージョンを変えずに行う
+fn format_porcelain(worktrees: &[WorktreeInfo]) -> String {
+    let mut output = output::porcelain_header();
+    for worktree in worktrees {
+        output.push_str(&format!("worktree {}\n", output::escape(&worktree.path.display().to_string())));
+        output.push_str(&format!("name {}\n", output::escape(&worktree.name)));
+        if worktree.is_detached {
+            output.push_str("detached\n");
+        } else {
+            output.push_str(&format!("branch {}\n", output::escape(&worktree.branch)));
+        }
+        if let Some(head) = worktree.head {
+            output.push_str(&format!("head {}\n", head));
+        }
+        if let Some(status) = &worktree.status {
+            output.push_str(&format!("status {}\n", status_key(&status.working_tree_status)));
+            if let Some(upstream) = &status.upstream {
+                output.push_str(&format!(
+                    "upstream {} {} {}\n",
+                    output::escape(upstream),
+                    status.ahead_count,
+                    status.behind_count
+                ));
+            }
+        }
+        if worktree.is_main {
+            output.push_str("main\n");
+        }
+        if worktree.is_current {
+            output.push_str("current\n");
+        }
+        match (&worktree.is_locked, &worktree.lock_reason) {
+            (true, Some(reason)) => output.push_str(&format!("locked {}\n", output::escape(reason))),
+            (true, None) => output.push_str("locked\n"),
+            (false, _) => {}
+        }
+        if worktree.is_prunable {
+            output.push_str("prunable\n");
+        }
+        output.push('\n');
+    }
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{git, setup_git_repo_with_worktree};
     use tempfile::tempdir;
     use std::fs;
-    use std::process::Command;
-
-    fn setup_git_repo_with_worktree() -> tempfile::TempDir {
-        let temp_dir = tempdir().unwrap();
-        let repo_path = temp_dir.path();
-        
-        // Git リポジトリを初期化
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .expect("Failed to init git repo");
-        
-        // 設定
-        Command::new("git")
-            .args(&["config", "user.name", "Test User"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["config", "user.email", "test@example.com"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // 初期ファイルとコミットを作成
-        fs::write(repo_path.join("README.md"), "# Test Repo").unwrap();
-        
-        Command::new("git")
-            .args(&["add", "."])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["commit", "-m", "Initial commit"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // テスト用のworktreeを作成
-        let worktree_path = repo_path.join("feature-test");
-        Command::new("git")
-            .args(&["worktree", "add", "-b", "feature-test", &worktree_path.to_string_lossy()])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        temp_dir
-    }
-
+    
     #[test]
     fn test_list_command_new_creates_instance() {
         // What: ListCommand::newが正しくインスタンスを作成するかテスト
-        let cmd = ListCommand::new(true);
-        assert_eq!(cmd.names_only, true);
+        let cmd = ListCommand::new(true, ListFormat::Builtin(OutputFormat::Text), false, None);
+        assert!(cmd.names_only);
+        assert_eq!(cmd.format, ListFormat::Builtin(OutputFormat::Text));
+        assert!(!cmd.refresh);
         
-        let cmd = ListCommand::new(false);
-        assert_eq!(cmd.names_only, false);
+        let cmd = ListCommand::new(false, ListFormat::Builtin(OutputFormat::Porcelain), false, None);
+        assert!(!cmd.names_only);
+        assert_eq!(cmd.format, ListFormat::Builtin(OutputFormat::Porcelain));
     }
 
     #[test]
@@ -122,7 +972,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let cmd = ListCommand::new(false);
+        let cmd = ListCommand::new(false, ListFormat::Builtin(OutputFormat::Text), false, None);
         let result = cmd.execute();
         
         assert!(result.is_err());
@@ -135,15 +985,11 @@ mod tests {
         let repo_path = temp_dir.path();
         
         // Git リポジトリを初期化（worktreeなし）
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .expect("Failed to init git repo");
+        git(repo_path, &["init"]);
         
         std::env::set_current_dir(repo_path).unwrap();
         
-        let cmd = ListCommand::new(false);
+        let cmd = ListCommand::new(false, ListFormat::Builtin(OutputFormat::Text), false, None);
         let result = cmd.execute();
         
         // worktreeが見つからない場合は成功するが出力は空
@@ -157,13 +1003,329 @@ mod tests {
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
         // names_only = true の場合
-        let cmd = ListCommand::new(true);
+        let cmd = ListCommand::new(true, ListFormat::Builtin(OutputFormat::Text), false, None);
         let result = cmd.execute();
         assert!(result.is_ok());
         
         // names_only = false の場合
-        let cmd = ListCommand::new(false);
+        let cmd = ListCommand::new(false, ListFormat::Builtin(OutputFormat::Text), false, None);
         let result = cmd.execute();
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_list_command_from_linked_worktree() {
+        // What: リンクされたworktree内から実行しても一覧を取得できるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path().join("feature-test")).unwrap();
+        
+        let cmd = ListCommand::new(false, ListFormat::Builtin(OutputFormat::Text), false, None);
+        assert!(cmd.execute().is_ok());
+        
+        let worktrees = GitWorktree::new().unwrap().list_worktrees().unwrap();
+        assert!(worktrees.iter().any(|w| w.branch == "feature-test"));
+    }
+
+    #[test]
+    fn test_list_includes_main_worktree_first() {
+        // What: メインworktreeが先頭に含まれ、現在のworktreeが判定されるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path().join("feature-test")).unwrap();
+        
+        let worktrees = GitWorktree::new().unwrap().list_worktrees().unwrap();
+        
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert!(!worktrees[0].is_current);
+        assert_eq!(worktrees[0].name, "@");
+        assert_eq!(worktrees[0].path.canonicalize().unwrap(), temp_dir.path().canonicalize().unwrap());
+        assert!(!worktrees[1].is_main);
+        assert!(worktrees[1].is_current);
+    }
+
+    #[test]
+    fn test_list_loads_statuses_only_when_requested() {
+        // What: 一覧の取得ではステータスを調べず、load_statusesで並列に埋められるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        fs::write(temp_dir.path().join("feature-test/scratch.txt"), "scratch").unwrap();
+        // メインworktreeから見てworktreeのディレクトリが未追跡にならないようにする
+        fs::write(temp_dir.path().join(".git/info/exclude"), "feature-test\n").unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let mut worktrees = GitWorktree::new().unwrap().list_worktrees().unwrap();
+        assert!(worktrees.iter().all(|w| w.status.is_none()));
+        assert_eq!(status_label(&worktrees[0]), "?");
+        
+        load_statuses(&mut worktrees, &Default::default(), std::time::Duration::from_secs(10), None);
+        
+        assert_eq!(status_label(&worktrees[0]), "clean");
+        assert_eq!(status_label(&worktrees[1]), "dirty");
+        assert!(ListCommand::new(false, ListFormat::Builtin(OutputFormat::Text), false, None).execute().is_ok());
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        // What: カンマ区切りとリストの両方で列を指定でき、不明な列はエラーになるかテスト
+        let names = vec!["branch,status".to_string(), "sha".to_string()];
+        
+        let columns = Column::parse_list(&names).unwrap();
+        
+        assert_eq!(columns, vec![Column::Branch, Column::Status, Column::Sha]);
+        let result = Column::parse_list(&["branch,unknown".to_string()]);
+        assert!(matches!(result.unwrap_err(), GitGardenerError::UnknownColumn { name, .. } if name == "unknown"));
+    }
+
+    #[test]
+    fn test_format_table_columns() {
+        // What: 選んだ列の値が揃えて表示され、コミット・ステータス・フラグが反映されるかテスト
+        colored::control::set_override(false);
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        let mut worktrees = git_worktree.list_worktrees().unwrap();
+        worktrees[1].is_locked = true;
+        load_statuses(&mut worktrees, &Default::default(), std::time::Duration::from_secs(10), None);
+        let commits: Vec<_> = worktrees.iter().map(|w| w.head.and_then(|oid| git_worktree.commit_summary(oid))).collect();
+        let now = commits[0].as_ref().unwrap().time + 3 * 86_400;
+        let columns = Column::parse_list(&["branch,ahead-behind,age,subject,flags".to_string()]).unwrap();
+        
+        let table = format_table(&columns, &worktrees[1..], &commits[1..], now);
+        
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "BRANCH       UPSTREAM AGE SUBJECT        FLAGS");
+        assert_eq!(lines[2], "feature-test -        3d  Initial commit [locked]");
+    }
+
+    #[test]
+    fn test_truncate_subject() {
+        // What: 長い件名が指定幅に切り詰められるかテスト
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a very long subject", 10), "a very lo…");
+    }
+
+    #[test]
+    fn test_format_worktrees_structured() {
+        // What: JSON・TSV・porcelainにworktreeの情報とステータスが含まれるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        fs::write(temp_dir.path().join(".git/info/exclude"), "feature-test\n").unwrap();
+        fs::write(temp_dir.path().join("feature-test/scratch.txt"), "scratch").unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let mut worktrees = GitWorktree::new().unwrap().list_worktrees().unwrap();
+        load_statuses(&mut worktrees, &Default::default(), std::time::Duration::from_secs(10), None);
+        
+        let json = format_worktrees(OutputFormat::Json, &worktrees).unwrap();
+        let tsv = format_worktrees(OutputFormat::Tsv, &worktrees).unwrap();
+        let porcelain = format_worktrees(OutputFormat::Porcelain, &worktrees).unwrap();
+        
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["worktrees"][0]["is_main"], true);
+        assert_eq!(value["worktrees"][1]["branch"], "feature-test");
+        assert_eq!(value["worktrees"][1]["status"]["working_tree_status"], "dirty");
+        assert_eq!(value["worktrees"][1]["status"]["untracked_count"], 1);
+        let rows: Vec<Vec<&str>> = tsv.lines().map(|line| line.split('\t').collect()).collect();
+        assert_eq!(rows[0][..5], ["name", "branch", "path", "head", "status"]);
+        assert_eq!(rows[2][1], "feature-test");
+        assert_eq!(rows[2][4], "dirty");
+        assert!(porcelain.starts_with("version 1\n\n"));
+        assert!(porcelain.contains("branch feature-test\nhead "));
+        assert!(porcelain.contains("status dirty\n"));
+        assert!(ListCommand::new(false, ListFormat::Builtin(OutputFormat::Json), false, None).execute().is_ok());
+    }
+
+    #[test]
+    fn test_list_format_resolves_builtin_named_and_inline() {
+        // What: --formatの値が組み込みの形式・設定の名前付きテンプレート・テンプレートとして解決されるかテスト
+        let formats = BTreeMap::from([("fzf".to_string(), "{branch}\\t{path}".to_string())]);
+        
+        assert_eq!(ListFormat::parse("JSON"), ListFormat::Builtin(OutputFormat::Json));
+        assert_eq!(ListFormat::parse("fzf"), ListFormat::Template("fzf".to_string()));
+        assert!(ListFormat::template("fzf", Some(&formats)).is_ok());
+        assert!(ListFormat::template("{name}", None).is_ok());
+        let result = ListFormat::template("dashboard", Some(&formats));
+        assert!(matches!(result.unwrap_err(), GitGardenerError::UnknownFormat { available, .. } if available == "fzf"));
+        let result = ListFormat::template("{nope}", None);
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidTemplate { .. }));
+    }
+    
+    #[test]
+    fn test_render_template_with_status_fields() {
+        // What: テンプレートでworktreeの情報・ステータス・コミットのフィールドを出力できるかテスト
+        colored::control::set_override(false);
+        let temp_dir = setup_git_repo_with_worktree();
+        fs::write(temp_dir.path().join(".git/info/exclude"), "feature-test\n").unwrap();
+        fs::write(temp_dir.path().join("feature-test/scratch.txt"), "scratch").unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        let mut worktrees = git_worktree.list_worktrees().unwrap();
+        load_statuses(&mut worktrees, &Default::default(), std::time::Duration::from_secs(10), None);
+        let rows: Vec<_> = worktrees.iter().map(|w| (w, w.head.and_then(|oid| git_worktree.commit_summary(oid)))).collect();
+        let template = ListFormat::template(
+            "{name:<}|{status}{?status.untracked} +{status.untracked}{/}{?main} (main){/}|{subject}",
+            None,
+        )
+        .unwrap();
+        
+        let lines = template.render_all(&rows, |(worktree, commit), field| template_value(worktree, commit.as_ref(), 0, field));
+        
+        assert_eq!(lines, vec![
+            "@           |clean (main)|Initial commit",
+            "feature-test|dirty +1|Initial commit",
+        ]);
+        let cmd = ListCommand::new(false, ListFormat::Template("{branch}\\t{status.ahead}".to_string()), false, None);
+        assert!(cmd.execute().is_ok());
+    }
+    
+    #[test]
+    fn test_list_filter_combinations() {
+        // What: --dirty/--clean/--merged/--locked/--matchの絞り込みと組み合わせをテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        let repo_path = temp_dir.path();
+        // feature-twoは独自のコミットを持ち、feature-testはmainに取り込まれた状態にする
+        git(repo_path, &["worktree", "add", "-b", "feature-two", "feature-two"]);
+        git(&repo_path.join("feature-two"), &["commit", "-q", "--allow-empty", "-m", "Two"]);
+        git(repo_path, &["commit", "-q", "--allow-empty", "-m", "Main"]);
+        git(repo_path, &["worktree", "lock", "feature-two"]);
+        fs::write(repo_path.join(".git/info/exclude"), "feature-test\nfeature-two\n").unwrap();
+        fs::write(repo_path.join("feature-test/scratch.txt"), "scratch").unwrap();
+        std::env::set_current_dir(repo_path).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        let mut all = git_worktree.list_worktrees().unwrap();
+        load_statuses(&mut all, &Default::default(), std::time::Duration::from_secs(10), None);
+        let base = all[0].branch.clone();
+        let filtered = |filter: ListFilter| {
+            let mut worktrees = all.clone();
+            filter.retain(&git_worktree, Some(&base), &mut worktrees).unwrap();
+            worktrees.into_iter().map(|w| w.name).collect::<Vec<_>>()
+        };
+        
+        assert_eq!(filtered(ListFilter { dirty: true, ..Default::default() }), vec!["feature-test"]);
+        assert_eq!(filtered(ListFilter { clean: true, ..Default::default() }), vec!["@", "feature-two"]);
+        assert_eq!(filtered(ListFilter { merged: true, ..Default::default() }), vec!["feature-test"]);
+        assert_eq!(filtered(ListFilter { locked: true, ..Default::default() }), vec!["feature-two"]);
+        assert_eq!(filtered(ListFilter { pattern: Some("feature-*".to_string()), ..Default::default() }).len(), 2);
+        assert_eq!(
+            filtered(ListFilter { pattern: Some("feature-*".to_string()), clean: true, ..Default::default() }),
+            vec!["feature-two"]
+        );
+        assert!(filtered(ListFilter { stale: Some(30), ..Default::default() }).is_empty());
+        
+        let filter = ListFilter { dirty: true, ..Default::default() };
+        for format in ["text", "json", "{branch}"] {
+            let cmd = ListCommand::new(false, ListFormat::parse(format), false, None)
+                .with_filter(filter.clone())
+                .with_sort(Some(SortKey::Status));
+            assert!(cmd.execute().is_ok());
+        }
+    }
+    
+    #[test]
+    fn test_sort_rows() {
+        // What: ブランチ名・コミットの新しさ・ステータスで並べ替えられるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        fs::write(temp_dir.path().join(".git/info/exclude"), "feature-test\n").unwrap();
+        fs::write(temp_dir.path().join("feature-test/scratch.txt"), "scratch").unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let mut worktrees = GitWorktree::new().unwrap().list_worktrees().unwrap();
+        load_statuses(&mut worktrees, &Default::default(), std::time::Duration::from_secs(10), None);
+        worktrees[0].branch = "zeta".to_string();
+        let commit = |time: i64| Some(CommitSummary { short_id: String::new(), subject: String::new(), time });
+        let mut rows = vec![(worktrees[0].clone(), commit(100)), (worktrees[1].clone(), commit(200))];
+        let names = |rows: &[(WorktreeInfo, Option<CommitSummary>)]| rows.iter().map(|(w, _)| w.name.clone()).collect::<Vec<_>>();
+        
+        sort_rows(&mut rows, SortKey::Branch);
+        assert_eq!(names(&rows), vec!["feature-test", "@"]);
+        sort_rows(&mut rows, SortKey::Path);
+        assert_eq!(names(&rows), vec!["@", "feature-test"]);
+        sort_rows(&mut rows, SortKey::Age);
+        assert_eq!(names(&rows), vec!["feature-test", "@"]);
+        sort_rows(&mut rows, SortKey::Path);
+        sort_rows(&mut rows, SortKey::Status);
+        assert_eq!(names(&rows), vec!["feature-test", "@"]);
+    }
+    
+    #[test]
+    fn test_format_tree_groups_by_branch_prefix() {
+        // What: ブランチ名の階層ごとにまとめ、1つだけの階層をまとめ、件数・変更・aheadを集計するかテスト
+        colored::control::set_override(false);
+        let temp_dir = tempdir().unwrap();
+        git(temp_dir.path(), &["init", "-q"]);
+        let clean = GitStatus::from_path(temp_dir.path()).unwrap();
+        let worktree = |branch: &str, dirty: bool, ahead: u32| WorktreeInfo {
+            name: branch.replace('/', "-"),
+            path: std::path::PathBuf::from(format!("/repo/{}", branch)),
+            branch: branch.to_string(),
+            is_prunable: false,
+            is_main: branch == "main",
+            is_current: branch == "main",
+            is_detached: false,
+            is_locked: false,
+            lock_reason: None,
+            head: None,
+            status: Some(GitStatus {
+                working_tree_status: match (dirty, ahead) {
+                    (true, _) => WorktreeStatus::Dirty,
+                    (false, 0) => WorktreeStatus::Clean,
+                    (false, _) => WorktreeStatus::Ahead,
+                },
+                upstream: Some("origin/x".to_string()),
+                ahead_count: ahead,
+                ..clean.clone()
+            }),
+        };
+        let worktrees = vec![
+            worktree("main", false, 0),
+            worktree("feature/login", true, 2),
+            worktree("user/alice/spike", false, 1),
+            worktree("feature/signup", false, 0),
+            worktree("user/alice/docs", true, 0),
+        ];
+        
+        let tree = format_tree(&worktrees);
+        
+        let lines: Vec<&str> = tree.lines().collect();
+        assert_eq!(lines, vec![
+            "@* main       clean /repo/main",
+            "   feature/ (2 worktrees, 1 dirty, ↑2)",
+            "   ├── login  dirty /repo/feature/login",
+            "   └── signup clean /repo/feature/signup",
+            "   user/alice/ (2 worktrees, 1 dirty, ↑1)",
+            "   ├── spike  ↑1    /repo/user/alice/spike",
+            "   └── docs   dirty /repo/user/alice/docs",
+        ]);
+        assert_eq!(TreeGroup::build(&worktrees).stats(), TreeStats { count: 5, dirty: 2, ahead: 3, behind: 0 });
+    }
+    
+    #[test]
+    fn test_format_porcelain_flags_main_and_current() {
+        // What: porcelain出力にメイン・現在・ロック中のworktreeのフラグが含まれ、値の改行がエスケープされるかテスト
+        let worktree = |name: &str, is_main: bool, is_current: bool| WorktreeInfo {
+            name: name.to_string(),
+            path: std::path::PathBuf::from(format!("/repo/{}", name)),
+            branch: name.to_string(),
+            is_prunable: false,
+            is_main,
+            is_current,
+            is_detached: false,
+            is_locked: false,
+            lock_reason: None,
+            head: None,
+            status: None,
+        };
+        let locked = WorktreeInfo {
+            is_locked: true,
+            lock_reason: Some("on usb drive\nunplugged".to_string()),
+            ..worktree("usb", false, false)
+        };
+        
+        let output = format_porcelain(&[worktree("main", true, false), worktree("feature", false, true), locked]);
+        
+        assert_eq!(
+            output,
+            "version 1\n\n\
+             worktree /repo/main\nname main\nbranch main\nmain\n\n\
+             worktree /repo/feature\nname feature\nbranch feature\ncurrent\n\n\
+             worktree /repo/usb\nname usb\nbranch usb\nlocked on usb drive\\nunplugged\n\n"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::error::{GitGardenerError, Result};
+use crate::git::{GitWorktree, WorktreeInfo};
+
+pub struct LockCommand {
+    pub worktree: String,
+    pub reason: Option<String>,
+}
+
+impl LockCommand {
+    pub fn new(worktree: String, reason: Option<String>) -> Self {
+        Self { worktree, reason }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let worktree_info = find_worktree(&git_worktree, &self.worktree)?;
+
+        git_worktree.lock_worktree(&worktree_info.name, self.reason.as_deref())?;
+
+        match &self.reason {
+            Some(reason) => println!("✓ Locked worktree '{}': {}", self.worktree, reason),
+            None => println!("✓ Locked worktree '{}'", self.worktree),
+        }
+        Ok(())
+    }
+}
+
+pub struct UnlockCommand {
+    pub worktree: String,
+}
+
+impl UnlockCommand {
+    pub fn new(worktree: String) -> Self {
+        Self { worktree }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let worktree_info = find_worktree(&git_worktree, &self.worktree)?;
+
+        git_worktree.unlock_worktree(&worktree_info.name)?;
+
+        println!("✓ Unlocked worktree '{}'", self.worktree);
+        Ok(())
+    }
+}
+
+// worktree名またはブランチ名でworktreeを探す
+fn find_worktree(git_worktree: &GitWorktree, name: &str) -> Result<WorktreeInfo> {
+    git_worktree
+        .list_worktrees()?
+        .into_iter()
+        .find(|w| w.name == name || w.branch == name)
+        .ok_or_else(|| GitGardenerError::WorktreeNotFound { name: name.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::setup_git_repo_with_worktree;
+    use crate::commands::remove::RemoveCommand;
+
+    #[test]
+    fn test_lock_command_records_reason() {
+        // What: ロックの状態と理由がworktree一覧に反映されるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = LockCommand::new("feature-test".to_string(), Some("on usb drive".to_string())).execute();
+
+        assert!(result.is_ok());
+        let worktree = find_worktree(&GitWorktree::new().unwrap(), "feature-test").unwrap();
+        assert!(worktree.is_locked);
+        assert_eq!(worktree.lock_reason.as_deref(), Some("on usb drive"));
+
+        // 二重ロックはエラー
+        let result = LockCommand::new("feature-test".to_string(), None).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::WorktreeLocked { .. }));
+    }
+
+    #[test]
+    fn test_locked_worktree_is_not_removed_without_force() {
+        // What: ロックされたworktreeは--forceなしでは削除されず、アンロック後は削除できるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        LockCommand::new("feature-test".to_string(), None).execute().unwrap();
+
+        let result = RemoveCommand::new("feature-test".to_string(), false, false).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::WorktreeLocked { .. }));
+        assert!(temp_dir.path().join("feature-test").exists());
+
+        UnlockCommand::new("feature-test".to_string()).execute().unwrap();
+        let result = RemoveCommand::new("feature-test".to_string(), false, false).execute();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_lock_and_unlock_errors() {
+        // What: メインworktreeのロックや、ロックされていないworktreeのアンロックがエラーになるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = LockCommand::new("@".to_string(), None).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::MainWorktree { .. }));
+
+        let result = UnlockCommand::new("feature-test".to_string()).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::WorktreeNotLocked { .. }));
+    }
+}
@@ -1,7 +1,16 @@
 pub mod add;
 pub mod cd;
+pub mod clean;
 pub mod completion;
+pub mod du;
+pub mod hooks;
 pub mod init;
 pub mod list;
+pub mod lock;
+pub mod mv;
+pub mod prompt;
+pub mod prune;
 pub mod remove;
+pub mod rename;
+pub mod run;
 pub mod shell_init;
\ No newline at end of file
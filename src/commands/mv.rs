@@ -0,0 +1,98 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::{remove_empty_parents, GitWorktree};
+use crate::state;
+use std::path::PathBuf;
+
+pub struct MoveCommand {
+    pub worktree: String,
+    pub new_path: PathBuf,
+}
+
+impl MoveCommand {
+    pub fn new(worktree: String, new_path: PathBuf) -> Self {
+        Self { worktree, new_path }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let repo_root = git_worktree.get_repository_root()?;
+        let config = Config::load_for_repo(&repo_root)?;
+
+        let worktree_info = git_worktree
+            .list_worktrees()?
+            .into_iter()
+            .find(|w| w.name == self.worktree || w.branch == self.worktree)
+            .ok_or_else(|| GitGardenerError::WorktreeNotFound { name: self.worktree.clone() })?;
+
+        // 相対パスはカレントディレクトリ基準
+        let target = if self.new_path.is_absolute() {
+            self.new_path.clone()
+        } else {
+            std::env::current_dir()?.join(&self.new_path)
+        };
+
+        let new_path = git_worktree.move_worktree(&worktree_info.name, &target)?;
+        state::migrate_worktree(&git_worktree.get_state_dir(), &worktree_info.path, &new_path, None)?;
+
+        let base_dir = config.defaults.root_dir.unwrap_or_else(|| ".gardener".to_string());
+        remove_empty_parents(&worktree_info.path, &repo_root.join(base_dir));
+
+        println!("✓ Moved worktree '{}' to {}", self.worktree, new_path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, git, setup_git_repo};
+
+    fn setup_git_repo_with_worktree() -> tempfile::TempDir {
+        let temp_dir = setup_git_repo();
+        // テスト用のworktreeを.gardener/group配下に作成
+        add_worktree(temp_dir.path(), "feature-test", ".gardener/group/feature-test");
+        temp_dir
+    }
+
+    #[test]
+    fn test_move_command_relocates_worktree() {
+        // What: worktreeが移動され、gitから新しい場所で認識され、空の親ディレクトリが消えるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = MoveCommand::new("feature-test".to_string(), PathBuf::from("moved/feature-test")).execute();
+
+        assert!(result.is_ok());
+        let new_path = temp_dir.path().join("moved/feature-test").canonicalize().unwrap();
+        assert!(new_path.join("README.md").exists());
+        assert!(!temp_dir.path().join(".gardener/group").exists());
+
+        let worktree = GitWorktree::new()
+            .unwrap()
+            .list_worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|w| w.branch == "feature-test")
+            .unwrap();
+        assert_eq!(worktree.path.canonicalize().unwrap(), new_path);
+        assert!(!worktree.is_prunable);
+
+        let output = git(&new_path, &["status", "--short"]);
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_move_command_refuses_main_and_locked_worktrees() {
+        // What: メインworktreeとロック中のworktreeは移動できないかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = MoveCommand::new("@".to_string(), temp_dir.path().join("elsewhere")).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::MainWorktree { .. }));
+
+        GitWorktree::new().unwrap().lock_worktree("feature-test", None).unwrap();
+        let result = MoveCommand::new("feature-test".to_string(), temp_dir.path().join("elsewhere")).execute();
+        assert!(matches!(result.unwrap_err(), GitGardenerError::WorktreeLocked { .. }));
+    }
+}
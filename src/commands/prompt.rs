@@ -0,0 +1,108 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::status::WorktreeStatus;
+use crate::git::status_cache::StatusCache;
+use crate::git::{load_statuses, GitWorktree, WorktreeInfo};
+use super::list::status_label;
+
+pub struct PromptCommand {
+    pub refresh: bool,
+}
+
+impl PromptCommand {
+    pub fn new(refresh: bool) -> Self {
+        Self { refresh }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let config = Config::load_for_repo(&git_worktree.get_repository_root()?)?;
+
+        let mut current: Vec<WorktreeInfo> = git_worktree
+            .list_worktrees()?
+            .into_iter()
+            .filter(|w| w.is_current)
+            .collect();
+        if current.is_empty() {
+            return Err(GitGardenerError::NotInRepository);
+        }
+
+        // プロンプトは頻繁に呼ばれるので、変更のないworktreeはキャッシュから読む
+        let mut cache = StatusCache::load(&git_worktree.get_state_dir())?.refresh(self.refresh);
+        load_statuses(
+            &mut current,
+            &config.defaults.status_query(),
+            config.defaults.status_timeout(),
+            Some(&mut cache),
+        );
+        cache.save()?;
+
+        println!("{}", format_prompt(&current[0]));
+        Ok(())
+    }
+}
+
+// `ブランチ名[|進行中の操作] [ステータス]`（クリーンな場合はステータスを省く）
+fn format_prompt(worktree: &WorktreeInfo) -> String {
+    let mut prompt = worktree.branch.clone();
+    if let Some(operation) = worktree.status.as_ref().and_then(|status| status.operation) {
+        prompt.push_str(&format!("|{}", operation));
+    }
+    let is_clean = worktree
+        .status
+        .as_ref()
+        .is_some_and(|status| status.working_tree_status == WorktreeStatus::Clean);
+    if !is_clean {
+        prompt.push_str(&format!(" {}", status_label(worktree)));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::setup_git_repo;
+    use crate::git::status::GitStatus;
+    use std::fs;
+
+    #[test]
+    fn test_format_prompt_for_each_state() {
+        // What: クリーン・変更あり・取得できない場合のプロンプト表示をテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        let worktree = |status: Option<GitStatus>| WorktreeInfo {
+            name: "@".to_string(),
+            path: repo_path.to_path_buf(),
+            branch: "main".to_string(),
+            is_prunable: false,
+            is_main: true,
+            is_current: true,
+            is_detached: false,
+            is_locked: false,
+            lock_reason: None,
+            head: None,
+            status,
+        };
+
+        let clean = GitStatus::from_path(repo_path).unwrap();
+        fs::write(repo_path.join("scratch.txt"), "scratch").unwrap();
+        let dirty = GitStatus::from_path(repo_path).unwrap();
+
+        assert_eq!(format_prompt(&worktree(Some(clean))), "main");
+        assert_eq!(format_prompt(&worktree(Some(dirty))), "main dirty");
+        assert_eq!(format_prompt(&worktree(None)), "main ?");
+    }
+
+    #[test]
+    fn test_prompt_command_writes_status_cache() {
+        // What: promptの実行でステータスのキャッシュが保存されるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        std::env::set_current_dir(repo_path).unwrap();
+
+        assert!(PromptCommand::new(false).execute().is_ok());
+
+        let state_dir = GitWorktree::new().unwrap().get_state_dir();
+        assert!(StatusCache::cache_path(&state_dir).exists());
+    }
+}
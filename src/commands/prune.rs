@@ -0,0 +1,217 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::{remove_empty_parents, GitWorktree};
+use crate::output::{self, ChangeAction, OutputFormat, WorktreeChange};
+use crate::state;
+use colored::*;
+use std::path::Path;
+
+pub struct PruneCommand {
+    pub dry_run: bool,
+    pub with_branch: bool,
+    pub force: bool,
+    pub format: OutputFormat,
+}
+
+// pruneの対象になったworktree
+pub struct PruneCandidate {
+    pub name: String,
+    pub branch: Option<String>,
+    pub path: std::path::PathBuf,
+    pub reason: String,
+    pub is_locked: bool,
+}
+
+impl PruneCommand {
+    pub fn new(dry_run: bool, with_branch: bool, force: bool) -> Self {
+        Self {
+            dry_run,
+            with_branch,
+            force,
+            format: OutputFormat::Text,
+        }
+    }
+
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        output::init(self.format);
+        let git_worktree = GitWorktree::new()?;
+        let repo_root = git_worktree.get_repository_root()?;
+        let config = Config::load_for_repo(&repo_root)?;
+        let base_dir = repo_root.join(config.defaults.root_dir.unwrap_or_else(|| ".gardener".to_string()));
+
+        let candidates = self.find_candidates(&git_worktree)?;
+        if candidates.is_empty() {
+            output::progress("Nothing to prune.");
+        }
+
+        // 1つpruneできなくても残りは続け、失敗した数を最後に返す
+        let mut changes = Vec::new();
+        let mut failed = 0;
+        for candidate in candidates {
+            let label = format!("'{}' ({})", candidate.name, candidate.reason);
+            let mut change = WorktreeChange {
+                action: ChangeAction::Pruned,
+                name: candidate.name.clone(),
+                path: candidate.path.clone(),
+                branch: candidate.branch.clone(),
+                reason: Some(candidate.reason.clone()),
+                branch_created: false,
+                branch_removed: false,
+            };
+
+            // ロック中のworktreeは--forceなしでは残す
+            if candidate.is_locked && !self.force {
+                output::progress(format!("{} Skipped locked worktree {}", "-".yellow(), label));
+                changes.push(WorktreeChange {
+                    action: ChangeAction::Skipped,
+                    reason: Some("locked".to_string()),
+                    ..change
+                });
+                continue;
+            }
+
+            if self.dry_run {
+                output::progress(format!("Would prune worktree {}", label));
+                if let Some(branch) = candidate.branch.as_ref().filter(|_| self.with_branch) {
+                    output::progress(format!("Would remove branch '{}'", branch));
+                    change.branch_removed = true;
+                }
+                changes.push(change);
+                continue;
+            }
+
+            if let Err(e) = git_worktree.prune_worktree(&candidate.name, self.force) {
+                eprintln!("Failed to prune worktree {}: {}", label, e);
+                failed += 1;
+                continue;
+            }
+            output::progress(format!("✓ Pruned worktree {}", label));
+            if let Err(e) = state::forget_worktree(&git_worktree.get_state_dir(), &candidate.path) {
+                eprintln!("Failed to clear hook cache state for worktree {}: {}", label, e);
+            }
+
+            if let Some(branch) = candidate.branch.as_ref().filter(|_| self.with_branch) {
+                match git_worktree.delete_branch(branch) {
+                    Ok(()) => {
+                        output::progress(format!("✓ Removed branch '{}'", branch));
+                        change.branch_removed = true;
+                    }
+                    Err(e) => eprintln!("Failed to remove branch '{}': {}", branch, e),
+                }
+            }
+
+            cleanup_empty_dirs(&candidate.path, &base_dir);
+            changes.push(change);
+        }
+
+        output::print_changes(self.format, "prune", self.dry_run, &changes)?;
+        if failed > 0 {
+            return Err(GitGardenerError::WorktreesFailed { action: "prune", count: failed });
+        }
+        Ok(())
+    }
+
+    // ディレクトリが失われた、またはgitdirが壊れたworktree
+    pub fn find_candidates(&self, git_worktree: &GitWorktree) -> Result<Vec<PruneCandidate>> {
+        let mut candidates = Vec::new();
+        for worktree in git_worktree.list_worktrees()? {
+            if worktree.is_main {
+                continue;
+            }
+            if let Some(reason) = git_worktree.prune_reason(&worktree.name)? {
+                candidates.push(PruneCandidate {
+                    name: worktree.name,
+                    branch: (!worktree.is_detached).then_some(worktree.branch),
+                    path: worktree.path,
+                    reason,
+                    is_locked: worktree.is_locked,
+                });
+            }
+        }
+        Ok(candidates)
+    }
+}
+
+// 残った空のディレクトリと、空になった親ディレクトリを削除する（中身のあるディレクトリは残す）
+fn cleanup_empty_dirs(path: &Path, base_dir: &Path) {
+    if !path.starts_with(base_dir) {
+        return;
+    }
+    if path.exists() && std::fs::remove_dir(path).is_err() {
+        return;
+    }
+    remove_empty_parents(path, base_dir);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, setup_git_repo};
+    use std::fs;
+
+    // .gardener配下に2つのworktreeを作り、片方のディレクトリを削除する
+    fn setup_git_repo_with_missing_worktree() -> tempfile::TempDir {
+        let temp_dir = setup_git_repo();
+        for branch in ["feature/gone", "kept"] {
+            add_worktree(temp_dir.path(), branch, &format!(".gardener/{}", branch));
+        }
+        fs::remove_dir_all(temp_dir.path().join(".gardener/feature/gone")).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_prune_command_dry_run_changes_nothing() {
+        // What: --dry-runでは候補を報告するだけで何も削除しないかテスト
+        let temp_dir = setup_git_repo_with_missing_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let cmd = PruneCommand::new(true, true, false);
+
+        let candidates = cmd.find_candidates(&GitWorktree::new().unwrap()).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].branch.as_deref(), Some("feature/gone"));
+        assert_eq!(candidates[0].reason, "directory missing");
+
+        assert!(cmd.execute().is_ok());
+        let git_worktree = GitWorktree::new().unwrap();
+        assert_eq!(git_worktree.list_worktrees().unwrap().len(), 3);
+        assert!(git_worktree.branch_exists("feature/gone").unwrap());
+    }
+
+    #[test]
+    fn test_prune_command_prunes_missing_worktree_and_branch() {
+        // What: 失われたworktreeの管理情報・ブランチ・空ディレクトリが削除され、有効なworktreeは残るかテスト
+        let temp_dir = setup_git_repo_with_missing_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = PruneCommand::new(false, true, false).execute();
+
+        assert!(result.is_ok());
+        let git_worktree = GitWorktree::new().unwrap();
+        let worktrees = git_worktree.list_worktrees().unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees.iter().any(|w| w.branch == "kept"));
+        assert!(!git_worktree.branch_exists("feature/gone").unwrap());
+        assert!(!temp_dir.path().join(".gardener/feature").exists());
+        assert!(temp_dir.path().join(".gardener/kept").exists());
+    }
+
+    #[test]
+    fn test_prune_command_skips_locked_worktree_without_force() {
+        // What: ロック中のworktreeは--forceなしではpruneされないかテスト
+        let temp_dir = setup_git_repo_with_missing_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        git_worktree.lock_worktree("gone", Some("on usb drive")).unwrap();
+
+        PruneCommand::new(false, false, false).execute().unwrap();
+        assert_eq!(git_worktree.list_worktrees().unwrap().len(), 3);
+
+        PruneCommand::new(false, false, true).execute().unwrap();
+        assert_eq!(git_worktree.list_worktrees().unwrap().len(), 2);
+    }
+}
@@ -1,21 +1,32 @@
 use crate::error::Result;
 use crate::git::GitWorktree;
-use std::process::Command;
+use crate::output::{self, ChangeAction, OutputFormat, WorktreeChange};
+use crate::state;
 
 pub struct RemoveCommand {
     pub worktree: String,
     pub with_branch: bool,
+    pub force: bool,
+    pub format: OutputFormat,
 }
 
 impl RemoveCommand {
-    pub fn new(worktree: String, with_branch: bool) -> Self {
+    pub fn new(worktree: String, with_branch: bool, force: bool) -> Self {
         Self {
             worktree,
             with_branch,
+            force,
+            format: OutputFormat::Text,
         }
     }
     
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+    
     pub fn execute(&self) -> Result<()> {
+        output::init(self.format);
         let git_worktree = GitWorktree::new()?;
         
         // worktreeの情報を取得
@@ -27,98 +38,65 @@ impl RemoveCommand {
                 name: self.worktree.clone() 
             })?;
         
+        if worktree_info.is_main {
+            return Err(crate::error::GitGardenerError::MainWorktree { action: "removed" });
+        }
+        
         let branch_name = worktree_info.branch.clone();
+        let is_detached = worktree_info.is_detached;
+        let mut change = WorktreeChange {
+            action: ChangeAction::Removed,
+            name: worktree_info.name.clone(),
+            path: worktree_info.path.clone(),
+            branch: (!is_detached).then(|| branch_name.clone()),
+            reason: None,
+            branch_created: false,
+            branch_removed: false,
+        };
         
         // worktreeを削除
-        git_worktree.remove_worktree(&worktree_info.name, false)?;
+        git_worktree.remove_worktree(&worktree_info.name, self.force)?;
         
-        println!("✓ Removed worktree '{}'", self.worktree);
+        output::progress(format!("✓ Removed worktree '{}'", self.worktree));
+        if let Err(e) = state::forget_worktree(&git_worktree.get_state_dir(), &worktree_info.path) {
+            eprintln!("Failed to clear hook cache state for '{}': {}", self.worktree, e);
+        }
         
         // --with-branchが指定されていればブランチも削除
-        if self.with_branch {
-            let output = Command::new("git")
-                .args(&["branch", "-D", &branch_name])
-                .output()?;
-            
-            if output.status.success() {
-                println!("✓ Removed branch '{}'", branch_name);
-            } else {
-                let error_msg = String::from_utf8_lossy(&output.stderr);
-                eprintln!("Failed to remove branch '{}': {}", branch_name, error_msg);
+        if self.with_branch && is_detached {
+            output::progress(format!("Worktree '{}' has a detached HEAD; no branch to remove", self.worktree));
+        } else if self.with_branch {
+            match git_worktree.delete_branch(&branch_name) {
+                Ok(()) => {
+                    output::progress(format!("✓ Removed branch '{}'", branch_name));
+                    change.branch_removed = true;
+                }
+                Err(e) => eprintln!("Failed to remove branch '{}': {}", branch_name, e),
             }
         }
         
-        Ok(())
+        output::print_changes(self.format, "remove", false, &[change])
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{git, setup_git_repo_with_worktree};
     use tempfile::tempdir;
     use std::fs;
-    use std::process::Command;
-
-    fn setup_git_repo_with_worktree() -> tempfile::TempDir {
-        let temp_dir = tempdir().unwrap();
-        let repo_path = temp_dir.path();
-        
-        // Git リポジトリを初期化
-        Command::new("git")
-            .args(&["init"])
-            .current_dir(repo_path)
-            .output()
-            .expect("Failed to init git repo");
-        
-        // 設定
-        Command::new("git")
-            .args(&["config", "user.name", "Test User"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["config", "user.email", "test@example.com"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // 初期ファイルとコミットを作成
-        fs::write(repo_path.join("README.md"), "# Test Repo").unwrap();
-        
-        Command::new("git")
-            .args(&["add", "."])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        Command::new("git")
-            .args(&["commit", "-m", "Initial commit"])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        // テスト用のworktreeを作成
-        let worktree_path = repo_path.join("feature-test");
-        Command::new("git")
-            .args(&["worktree", "add", "-b", "feature-test", &worktree_path.to_string_lossy()])
-            .current_dir(repo_path)
-            .output()
-            .unwrap();
-        
-        temp_dir
-    }
-
+    
     #[test]
     fn test_remove_command_new_creates_instance() {
         // What: RemoveCommand::newが正しくインスタンスを作成するかテスト
-        let cmd = RemoveCommand::new("test-branch".to_string(), false);
+        let cmd = RemoveCommand::new("test-branch".to_string(), false, false);
         assert_eq!(cmd.worktree, "test-branch");
-        assert_eq!(cmd.with_branch, false);
+        assert!(!cmd.with_branch);
+        assert!(!cmd.force);
         
-        let cmd = RemoveCommand::new("test-branch".to_string(), true);
+        let cmd = RemoveCommand::new("test-branch".to_string(), true, false);
         assert_eq!(cmd.worktree, "test-branch");
-        assert_eq!(cmd.with_branch, true);
+        assert!(cmd.with_branch);
     }
 
     #[test]
@@ -127,7 +105,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let cmd = RemoveCommand::new("test".to_string(), false);
+        let cmd = RemoveCommand::new("test".to_string(), false, false);
         let result = cmd.execute();
         
         assert!(result.is_err());
@@ -140,7 +118,7 @@ mod tests {
         let temp_dir = setup_git_repo_with_worktree();
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
-        let cmd = RemoveCommand::new("nonexistent-worktree".to_string(), false);
+        let cmd = RemoveCommand::new("nonexistent-worktree".to_string(), false, false);
         let result = cmd.execute();
         
         assert!(result.is_err());
@@ -161,7 +139,7 @@ mod tests {
         let worktrees_before = git_worktree.list_worktrees().unwrap();
         assert!(worktrees_before.iter().any(|w| w.branch == "feature-test"));
         
-        let cmd = RemoveCommand::new("feature-test".to_string(), false);
+        let cmd = RemoveCommand::new("feature-test".to_string(), false, false);
         let result = cmd.execute();
         
         assert!(result.is_ok());
@@ -171,6 +149,25 @@ mod tests {
         assert!(!worktrees_after.iter().any(|w| w.branch == "feature-test"));
     }
 
+    #[test]
+    fn test_remove_command_forgets_hook_cache_state() {
+        // What: 削除したworktreeのフックのキャッシュ状態が取り除かれるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let state_dir = GitWorktree::new().unwrap().get_state_dir();
+        let worktree_path = temp_dir.path().join("feature-test");
+        crate::state::GardenerState::update(&state_dir, |state| {
+            state.record_hook_run("hook", &worktree_path, "hash");
+            true
+        })
+        .unwrap();
+        
+        RemoveCommand::new("feature-test".to_string(), false, false).execute().unwrap();
+        
+        let state = crate::state::GardenerState::load(&state_dir).unwrap();
+        assert!(state.find_hook_run("hook", &worktree_path).is_none());
+    }
+
     #[test]
     fn test_remove_command_with_branch_flag() {
         // What: --with-branchフラグでブランチも削除されるかテスト
@@ -178,24 +175,66 @@ mod tests {
         std::env::set_current_dir(temp_dir.path()).unwrap();
         
         // ブランチが存在することを確認
-        let output = Command::new("git")
-            .args(&["branch", "--list", "feature-test"])
-            .current_dir(temp_dir.path())
-            .output()
-            .unwrap();
+        let output = git(temp_dir.path(), &["branch", "--list", "feature-test"]);
         assert!(String::from_utf8_lossy(&output.stdout).contains("feature-test"));
         
-        let cmd = RemoveCommand::new("feature-test".to_string(), true);
+        let cmd = RemoveCommand::new("feature-test".to_string(), true, false);
         let result = cmd.execute();
         
         assert!(result.is_ok());
         
         // ブランチが削除されたことを確認
-        let output = Command::new("git")
-            .args(&["branch", "--list", "feature-test"])
-            .current_dir(temp_dir.path())
-            .output()
-            .unwrap();
+        let output = git(temp_dir.path(), &["branch", "--list", "feature-test"]);
         assert!(!String::from_utf8_lossy(&output.stdout).contains("feature-test"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_remove_command_force_removes_dirty_worktree() {
+        // What: 変更のあるworktreeは--forceなしで削除されず、--forceで削除されるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("feature-test/README.md"), "changed").unwrap();
+        
+        let result = RemoveCommand::new("feature-test".to_string(), false, false).execute();
+        assert!(matches!(
+            result.unwrap_err(),
+            crate::error::GitGardenerError::WorktreeDirty { .. }
+        ));
+        assert!(temp_dir.path().join("feature-test").exists());
+        
+        let result = RemoveCommand::new("feature-test".to_string(), false, true).execute();
+        assert!(result.is_ok());
+        assert!(!temp_dir.path().join("feature-test").exists());
+    }
+
+    #[test]
+    fn test_remove_command_from_linked_worktree() {
+        // What: リンクされたworktree内から別のworktreeを削除できるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        let other_path = temp_dir.path().join("other");
+        git(temp_dir.path(), &["worktree", "add", "-b", "other", &other_path.to_string_lossy()]);
+        std::env::set_current_dir(temp_dir.path().join("feature-test")).unwrap();
+        
+        let result = RemoveCommand::new("other".to_string(), true, false).execute();
+        
+        assert!(result.is_ok());
+        assert!(!other_path.exists());
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        assert!(!git_worktree.branch_exists("other").unwrap());
+    }
+
+    #[test]
+    fn test_remove_command_refuses_main_worktree() {
+        // What: メインworktreeの削除が拒否されるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        
+        let result = RemoveCommand::new("@".to_string(), false, true).execute();
+        
+        assert!(matches!(
+            result.unwrap_err(),
+            crate::error::GitGardenerError::MainWorktree { .. }
+        ));
+        assert!(temp_dir.path().join("README.md").exists());
+    }
+}
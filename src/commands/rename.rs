@@ -0,0 +1,171 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::{remove_empty_parents, GitWorktree};
+use crate::state;
+
+pub struct RenameCommand {
+    pub worktree: String,
+    pub new_branch: String,
+}
+
+impl RenameCommand {
+    pub fn new(worktree: String, new_branch: String) -> Self {
+        Self { worktree, new_branch }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let repo_root = git_worktree.get_repository_root()?;
+        let config = Config::load_for_repo(&repo_root)?;
+
+        let worktree_info = git_worktree
+            .list_worktrees()?
+            .into_iter()
+            .find(|w| w.name == self.worktree || w.branch == self.worktree)
+            .ok_or_else(|| GitGardenerError::WorktreeNotFound { name: self.worktree.clone() })?;
+
+        if worktree_info.is_main {
+            return Err(GitGardenerError::MainWorktree { action: "renamed" });
+        }
+        if worktree_info.is_detached {
+            return Err(GitGardenerError::Custom(format!(
+                "Worktree '{}' has a detached HEAD; there is no branch to rename",
+                self.worktree
+            )));
+        }
+        if worktree_info.is_locked {
+            return Err(GitGardenerError::WorktreeLocked {
+                name: worktree_info.name,
+                reason: worktree_info.lock_reason,
+            });
+        }
+
+        // 新しいブランチ名に合わせた場所（addと同じ<root_dir>/<branch>）へ移動する
+        let base_dir = repo_root.join(config.defaults.root_dir.unwrap_or_else(|| ".gardener".to_string()));
+        let new_path = base_dir.join(&self.new_branch);
+        if new_path.exists() {
+            return Err(GitGardenerError::WorktreePathExists {
+                path: new_path.display().to_string(),
+            });
+        }
+
+        git_worktree.rename_branch(&worktree_info.branch, &self.new_branch)?;
+        let new_path = match git_worktree.move_worktree(&worktree_info.name, &new_path) {
+            Ok(path) => path,
+            Err(e) => {
+                // 移動できなければブランチ名を元に戻す
+                let _ = git_worktree.rename_branch(&self.new_branch, &worktree_info.branch);
+                return Err(e);
+            }
+        };
+        if let Err(e) = git_worktree.rename_worktree_admin(&worktree_info.name, &self.new_branch) {
+            // 管理情報を改名できなければ、ディレクトリとブランチ名を元に戻す
+            let _ = git_worktree.move_worktree(&worktree_info.name, &worktree_info.path);
+            let _ = git_worktree.rename_branch(&self.new_branch, &worktree_info.branch);
+            return Err(e);
+        }
+
+        state::migrate_worktree(
+            &git_worktree.get_state_dir(),
+            &worktree_info.path,
+            &new_path,
+            Some((&worktree_info.branch, &self.new_branch)),
+        )?;
+        remove_empty_parents(&worktree_info.path, &base_dir);
+
+        println!(
+            "✓ Renamed '{}' to '{}' ({})",
+            worktree_info.branch,
+            self.new_branch,
+            new_path.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, setup_git_repo};
+    use crate::state::{HookHistory, HookRun, HookRunStatus};
+    use std::fs;
+
+    fn setup_git_repo_with_worktree() -> tempfile::TempDir {
+        let temp_dir = setup_git_repo();
+        // addと同じ配置でworktreeを作成
+        add_worktree(temp_dir.path(), "feature/old", ".gardener/feature/old");
+        temp_dir
+    }
+
+    #[test]
+    fn test_rename_command_renames_branch_and_directory() {
+        // What: ブランチ名・ディレクトリ・gitの管理情報・実行履歴が新しい名前に更新されるかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        let git_worktree = GitWorktree::new().unwrap();
+        let old_path = git_worktree
+            .list_worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|w| w.branch == "feature/old")
+            .unwrap()
+            .path;
+        let state_dir = git_worktree.get_state_dir();
+        let log_path = state::log_dir(&state_dir, "feature/old").join("1-0.log");
+        fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        fs::write(&log_path, "log").unwrap();
+        HookHistory::append(
+            &state_dir,
+            HookRun {
+                worktree: "feature/old".to_string(),
+                worktree_path: old_path.clone(),
+                hook: "npm ci".to_string(),
+                started_at: 1,
+                duration_ms: 10,
+                status: HookRunStatus::Success,
+                exit_code: None,
+                log_path: Some(log_path),
+            },
+        )
+        .unwrap();
+
+        let result = RenameCommand::new("feature/old".to_string(), "feature/new".to_string()).execute();
+
+        assert!(result.is_ok());
+        assert!(!git_worktree.branch_exists("feature/old").unwrap());
+        assert!(!old_path.exists());
+        assert!(!temp_dir.path().join(".gardener/feature/old").exists());
+
+        let worktree = git_worktree
+            .list_worktrees()
+            .unwrap()
+            .into_iter()
+            .find(|w| w.branch == "feature/new")
+            .unwrap();
+        assert_eq!(worktree.name, "feature-new");
+        assert_eq!(
+            worktree.path.canonicalize().unwrap(),
+            temp_dir.path().join(".gardener/feature/new").canonicalize().unwrap()
+        );
+
+        let run = &HookHistory::load(&state_dir).unwrap().runs[0];
+        assert_eq!(run.worktree, "feature/new");
+        assert_eq!(run.worktree_path, worktree.path);
+        assert!(run.log_path.as_ref().unwrap().exists());
+    }
+
+    #[test]
+    fn test_rename_command_keeps_branch_when_target_exists() {
+        // What: 移動先が既に存在する場合はブランチ名を変更しないかテスト
+        let temp_dir = setup_git_repo_with_worktree();
+        fs::create_dir_all(temp_dir.path().join(".gardener/taken")).unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let result = RenameCommand::new("feature/old".to_string(), "taken".to_string()).execute();
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::WorktreePathExists { .. }));
+        let git_worktree = GitWorktree::new().unwrap();
+        assert!(git_worktree.branch_exists("feature/old").unwrap());
+        assert!(!git_worktree.branch_exists("taken").unwrap());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::config::Config;
+use crate::error::{GitGardenerError, Result};
+use crate::git::GitWorktree;
+use crate::hooks::HookExecutor;
+use colored::*;
+use std::path::PathBuf;
+
+pub struct RunCommand {
+    pub task: Option<String>,
+    pub worktree: Option<String>,
+    pub all: bool,
+}
+
+impl RunCommand {
+    pub fn new(task: Option<String>, worktree: Option<String>, all: bool) -> Self {
+        Self {
+            task,
+            worktree,
+            all,
+        }
+    }
+
+    pub fn execute(&self) -> Result<()> {
+        let git_worktree = GitWorktree::new()?;
+        let repo_root = git_worktree.get_repository_root()?;
+        let config = Config::load_for_repo(&repo_root)?;
+        let tasks = config.tasks.unwrap_or_default();
+
+        // タスク名が指定されていなければ定義済みタスクを一覧表示
+        let Some(task_name) = &self.task else {
+            if tasks.is_empty() {
+                println!("No tasks defined in .gardener.yml.");
+            }
+            for (name, task) in &tasks {
+                println!("{:<25} {}", name.green(), task.command.as_deref().unwrap_or("").bright_black());
+            }
+            return Ok(());
+        };
+
+        let task = tasks.get(task_name).ok_or_else(|| GitGardenerError::TaskNotFound {
+            name: task_name.clone(),
+        })?;
+
+        let targets = self.resolve_targets(&git_worktree)?;
+        // 開発サーバーやウォッチャーのように終了しないタスクもあるので、出力はその場で表示する
+        let hook_executor = HookExecutor::with_state_dir(&git_worktree.get_state_dir()).with_live_output();
+
+        let mut failed = Vec::new();
+        for (path, branch) in &targets {
+            if targets.len() > 1 {
+                println!("{} {} ({})", "==>".blue().bold(), branch.green(), path.display());
+            }
+
+            match hook_executor.execute_hooks(path, branch, std::slice::from_ref(task)) {
+                Ok(_) => {}
+                // 単一worktreeの場合や中断された場合はそのままエラーを返す
+                Err(e) if targets.len() == 1 || matches!(e, GitGardenerError::Interrupted { .. }) => {
+                    return Err(e)
+                }
+                Err(e) => {
+                    eprintln!("{} {}", "✗".red(), e);
+                    failed.push(branch.clone());
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(GitGardenerError::Custom(format!(
+                "Task '{}' failed in: {}",
+                task_name,
+                failed.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+
+    // タスクを実行するworktreeの（パス, ブランチ）一覧を決める
+    fn resolve_targets(&self, git_worktree: &GitWorktree) -> Result<Vec<(PathBuf, String)>> {
+        if self.all {
+            let mut targets = Vec::new();
+            for worktree in git_worktree.list_worktrees()? {
+                // ディレクトリが失われたworktreeでは実行できないので、理由を示して飛ばす
+                let reason = if worktree.is_main {
+                    None
+                } else {
+                    git_worktree.prune_reason(&worktree.name)?
+                };
+                if let Some(reason) = reason {
+                    eprintln!("{} Skipped worktree '{}' ({})", "-".yellow(), worktree.branch, reason);
+                    continue;
+                }
+                targets.push((worktree.path, worktree.branch));
+            }
+            return Ok(targets);
+        }
+
+        match &self.worktree {
+            Some(name) => {
+                let worktree = git_worktree
+                    .list_worktrees()?
+                    .into_iter()
+                    .find(|w| w.name == *name || w.branch == *name)
+                    .ok_or_else(|| GitGardenerError::WorktreeNotFound { name: name.clone() })?;
+                Ok(vec![(worktree.path, worktree.branch)])
+            }
+            None => Ok(vec![git_worktree.get_current_worktree()?]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, commit_all, setup_git_repo};
+    use std::fs;
+
+    fn setup_git_repo_with_tasks() -> tempfile::TempDir {
+        let temp_dir = setup_git_repo();
+        fs::write(
+            temp_dir.path().join(".gardener.yml"),
+            "tasks:\n  touch:\n    command: \"echo ${BRANCH} > task.txt\"\n",
+        )
+        .unwrap();
+        commit_all(temp_dir.path(), "Add tasks");
+        add_worktree(temp_dir.path(), "feature-test", "feature-test");
+        temp_dir
+    }
+
+    #[test]
+    fn test_run_command_new_creates_instance() {
+        // What: RunCommand::newが正しくインスタンスを作成するかテスト
+        let cmd = RunCommand::new(Some("reset-db".to_string()), None, true);
+
+        assert_eq!(cmd.task, Some("reset-db".to_string()));
+        assert!(cmd.worktree.is_none());
+        assert!(cmd.all);
+    }
+
+    #[test]
+    fn test_run_command_fails_for_unknown_task() {
+        // What: 定義されていないタスクでエラーになるかテスト
+        let temp_dir = setup_git_repo_with_tasks();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = RunCommand::new(Some("missing".to_string()), None, false);
+        let result = cmd.execute();
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::TaskNotFound { .. }));
+    }
+
+    #[test]
+    fn test_run_command_runs_task_in_named_worktree() {
+        // What: --worktreeで指定したworktreeでタスクが実行されるかテスト
+        let temp_dir = setup_git_repo_with_tasks();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = RunCommand::new(Some("touch".to_string()), Some("feature-test".to_string()), false);
+        let result = cmd.execute();
+
+        assert!(result.is_ok());
+        let content = fs::read_to_string(temp_dir.path().join("feature-test/task.txt")).unwrap();
+        assert_eq!(content.trim(), "feature-test");
+        assert!(!temp_dir.path().join("task.txt").exists());
+    }
+
+    #[test]
+    fn test_run_command_runs_task_in_all_worktrees() {
+        // What: --allでメインを含む全worktreeでタスクが実行されるかテスト
+        let temp_dir = setup_git_repo_with_tasks();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = RunCommand::new(Some("touch".to_string()), None, true);
+        let result = cmd.execute();
+
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("task.txt").exists());
+        assert!(temp_dir.path().join("feature-test/task.txt").exists());
+    }
+
+    #[test]
+    fn test_run_command_all_skips_missing_worktrees() {
+        // What: --allでディレクトリが失われたworktreeを飛ばし、残りで実行されるかテスト
+        let temp_dir = setup_git_repo_with_tasks();
+        add_worktree(temp_dir.path(), "feature-gone", "feature-gone");
+        fs::remove_dir_all(temp_dir.path().join("feature-gone")).unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let cmd = RunCommand::new(Some("touch".to_string()), None, true);
+        let result = cmd.execute();
+
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("feature-test/task.txt").exists());
+        assert!(!temp_dir.path().join("feature-gone").exists());
+    }
+
+    #[test]
+    fn test_run_command_from_linked_worktree() {
+        // What: リンクされたworktree内ではメインの設定を読み、現在のworktreeでタスクを実行するかテスト
+        let temp_dir = setup_git_repo_with_tasks();
+        // コミットされていない設定はメインworktreeにしか存在しない
+        fs::write(
+            temp_dir.path().join(".gardener.yml"),
+            "tasks:\n  main-only:\n    command: \"echo ok > main-only.txt\"\n",
+        )
+        .unwrap();
+        std::env::set_current_dir(temp_dir.path().join("feature-test")).unwrap();
+        
+        let cmd = RunCommand::new(Some("main-only".to_string()), None, false);
+        let result = cmd.execute();
+        
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("feature-test/main-only.txt").exists());
+        assert!(!temp_dir.path().join("main-only.txt").exists());
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use crate::error::{GitGardenerError, Result};
+use crate::git::status::StatusQuery;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
@@ -12,12 +15,50 @@ pub struct Config {
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hooks: Option<Hooks>,
+    
+    // `git-gardener run <task>` で任意のworktreeで実行できる名前付きタスク
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks: Option<BTreeMap<String, Hook>>,
+    
+    // `git-gardener list --format <name>` で使える名前付きのテンプレート
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formats: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DefaultConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub root_dir: Option<String>,
+    
+    // ahead/behindの比較やcleanの--mergedで使うベースブランチ（省略時はメインworktreeのブランチ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+    
+    // listのステータスで未追跡ファイルを調べるか（省略時はtrue）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_untracked: Option<bool>,
+    
+    // worktreeごとのステータス取得を待つ時間（ミリ秒、省略時は1000）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_timeout_ms: Option<u64>,
+    
+    // listで表示する列（`[branch, status, path]`や`"branch,status,path"`）
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_one_or_many")]
+    pub list_columns: Option<Vec<String>>,
+}
+
+impl DefaultConfig {
+    pub fn status_query(&self) -> StatusQuery {
+        StatusQuery {
+            include_untracked: self.status_untracked.unwrap_or(true),
+            base_branch: self.base_branch.clone(),
+            ..Default::default()
+        }
+    }
+    
+    pub fn status_timeout(&self) -> Duration {
+        Duration::from_millis(self.status_timeout_ms.unwrap_or(1000))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,9 +67,10 @@ pub struct Hooks {
     pub post_create: Option<Vec<Hook>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Hook {
-    #[serde(rename = "type")]
+    // 省略時はcommandフックとして扱う
+    #[serde(rename = "type", default)]
     pub hook_type: HookType,
     
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -42,21 +84,58 @@ pub struct Hook {
     
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<std::collections::HashMap<String, String>>,
+    
+    // コマンドを実行するディレクトリ（worktreeからの相対パス）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    
+    // envにマージするdotenvファイル（worktreeからの相対パス、単一でもリストでも可）
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_one_or_many")]
+    pub env_file: Option<Vec<String>>,
+    
+    // 内容が前回の実行と同じならフックをスキップする入力ファイル（worktreeからの相対パス）
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_one_or_many")]
+    pub inputs: Option<Vec<String>>,
+    
+    // 入力が同じ場合にキャッシュから復元する出力（worktreeからの相対パス）
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_one_or_many")]
+    pub outputs: Option<Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum HookType {
     Copy,
+    #[default]
     Command,
 }
 
+// `key: value` と `key: [a, b]` の両方の書き方を受け付ける
+fn deserialize_one_or_many<'de, D>(deserializer: D) -> std::result::Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    
+    Ok(Option::<OneOrMany>::deserialize(deserializer)?.map(|value| match value {
+        OneOrMany::One(item) => vec![item],
+        OneOrMany::Many(items) => items,
+    }))
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             version: default_version(),
             defaults: DefaultConfig::default(),
             hooks: None,
+            tasks: None,
+            formats: None,
         }
     }
 }
@@ -94,6 +173,16 @@ impl Config {
     pub fn get_config_path(repo_path: &Path) -> PathBuf {
         repo_path.join(".gardener.yml")
     }
+    
+    // リポジトリの設定を読み込む（存在しない場合はデフォルト設定を使用）
+    pub fn load_for_repo(repo_path: &Path) -> Result<Self> {
+        let config_path = Self::get_config_path(repo_path);
+        if config_path.exists() {
+            Self::load_from_file(&config_path)
+        } else {
+            Ok(Self::default())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +239,7 @@ mod tests {
             to: None,
             command: Some("npm install".to_string()),
             env: Some(env),
+            ..Default::default()
         };
         
         let hooks = Hooks {
@@ -160,8 +250,11 @@ mod tests {
             version: "1.0".to_string(),
             defaults: DefaultConfig {
                 root_dir: Some(".gardener".to_string()),
+                ..Default::default()
             },
             hooks: Some(hooks),
+            tasks: None,
+            formats: None,
         };
         
         let temp_dir = tempdir().unwrap();
@@ -191,6 +284,7 @@ mod tests {
             to: Some("README.md".to_string()),
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let hooks = Hooks {
@@ -201,6 +295,8 @@ mod tests {
             version: "1.0".to_string(),
             defaults: DefaultConfig::default(),
             hooks: Some(hooks),
+            tasks: None,
+            formats: None,
         };
         
         let temp_dir = tempdir().unwrap();
@@ -232,4 +328,81 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), GitGardenerError::Custom(_)));
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_hook_cwd_and_env_file_deserialization() {
+        // What: cwdとenv_file（単一・リスト両方）が読み込めるかテスト
+        let yaml = r#"
+version: "1.0"
+hooks:
+  post_create:
+    - type: command
+      command: "npm install"
+      cwd: "packages/api"
+      env_file: ".env"
+    - type: command
+      command: "make"
+      env_file: [".env", ".env.local"]
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let hooks = config.hooks.unwrap().post_create.unwrap();
+        
+        assert_eq!(hooks[0].cwd, Some("packages/api".to_string()));
+        assert_eq!(hooks[0].env_file, Some(vec![".env".to_string()]));
+        assert!(hooks[1].cwd.is_none());
+        assert_eq!(
+            hooks[1].env_file,
+            Some(vec![".env".to_string(), ".env.local".to_string()])
+        );
+    }
+    
+    #[test]
+    fn test_hook_inputs_and_outputs_deserialization() {
+        // What: inputs/outputsが読み込めるかテスト
+        let yaml = r#"
+hooks:
+  post_create:
+    - type: command
+      command: "npm ci"
+      inputs: [package-lock.json]
+      outputs: node_modules
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let hooks = config.hooks.unwrap().post_create.unwrap();
+        
+        assert_eq!(hooks[0].inputs, Some(vec!["package-lock.json".to_string()]));
+        assert_eq!(hooks[0].outputs, Some(vec!["node_modules".to_string()]));
+    }
+    
+    #[test]
+    fn test_config_with_tasks_deserialization() {
+        // What: tasksセクションがHookと同じ形で読み込めるかテスト
+        let yaml = r#"
+tasks:
+  reset-db:
+    type: command
+    command: "make db-reset"
+    cwd: "packages/api"
+  regen-fixtures:
+    command: "npm run fixtures"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let tasks = config.tasks.unwrap();
+        
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks["reset-db"].command, Some("make db-reset".to_string()));
+        assert_eq!(tasks["reset-db"].cwd, Some("packages/api".to_string()));
+        assert_eq!(tasks["regen-fixtures"].hook_type, HookType::Command);
+    }
+    
+    #[test]
+    fn test_load_for_repo_falls_back_to_default() {
+        // What: 設定ファイルがない場合にデフォルト設定が返されるかテスト
+        let temp_dir = tempdir().unwrap();
+        
+        let config = Config::load_for_repo(temp_dir.path()).unwrap();
+        
+        assert!(config.hooks.is_none());
+        assert!(config.tasks.is_none());
+    }
+}
@@ -0,0 +1,453 @@
+use clap::ValueEnum;
+use git2::Repository;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+
+// gitに無視されたファイル（ビルド成果物など）の扱い
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoredMode {
+    // すべてのファイルを数える
+    #[default]
+    Include,
+    // 無視されたファイルを除いて数える
+    Exclude,
+    // 無視されたファイルだけを数える
+    Only,
+}
+
+// ディスク上の使用量（バイト）と、そのうち無視されたファイルの分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Usage {
+    pub bytes: u64,
+    pub ignored_bytes: u64,
+}
+
+impl Usage {
+    pub fn add(&mut self, other: Usage) {
+        self.bytes += other.bytes;
+        self.ignored_bytes += other.ignored_bytes;
+    }
+
+    pub fn subtract(&mut self, other: Usage) {
+        self.bytes -= other.bytes;
+        self.ignored_bytes -= other.ignored_bytes;
+    }
+
+    // --ignoredの指定に応じて数える量
+    pub fn counted(&self, mode: IgnoredMode) -> u64 {
+        match mode {
+            IgnoredMode::Include => self.bytes,
+            IgnoredMode::Exclude => self.bytes.saturating_sub(self.ignored_bytes),
+            IgnoredMode::Only => self.ignored_bytes,
+        }
+    }
+}
+
+// --depthで内訳を表示するディレクトリ（worktreeからの相対パス、深さは1から）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DirUsage {
+    pub path: PathBuf,
+    pub depth: usize,
+    #[serde(flatten)]
+    pub usage: Usage,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorktreeUsage {
+    pub usage: Usage,
+    pub entries: Vec<DirUsage>,
+    // ハードリンク・reflinkで他と共有している可能性のあるファイル（charge_sharedで重複を差し引く）
+    shared: Vec<SharedFile>,
+}
+
+// 共有されたデータを含むファイル（usageには一旦すべて数えてある）
+#[derive(Debug, Clone, PartialEq)]
+struct SharedFile {
+    // (デバイス, inode)。ハードリンクが1つだけならNone
+    inode: Option<(u64, u64)>,
+    allocated: u64,
+    // (デバイス, 物理オフセット, 長さ)
+    extents: Vec<(u64, u64, u64)>,
+    ignored: bool,
+    // このファイルを含む内訳（entriesの添字）
+    entries: Vec<usize>,
+}
+
+// worktreeの使用量を調べる（.gitと、skipにある別のworktreeのディレクトリは数えない）
+// 共有されたデータはworktreeごとにすべて数えるので、並べたあとでcharge_sharedを呼ぶ
+pub fn measure(root: &Path, depth: usize, skip: &[PathBuf]) -> WorktreeUsage {
+    let scan = Scan {
+        root,
+        repo: Repository::open(root).ok(),
+        depth,
+        skip,
+        reflinks: supports_reflinks(root),
+    };
+    let mut result = WorktreeUsage::default();
+    let mut open = Vec::new();
+    result.usage = scan.walk(root, Path::new(""), 0, false, &mut result, &mut open);
+    result
+}
+
+// 共有されたデータを、並び順で最初に現れたworktree（その中では最初に辿ったファイル）にだけ数える
+// （並列に調べても結果が実行ごとに変わらないよう、調べ終わってから順に差し引く）
+pub fn charge_shared(usages: &mut [WorktreeUsage]) {
+    let mut inodes = HashSet::new();
+    let mut extents = HashSet::new();
+
+    for usage in usages {
+        for file in std::mem::take(&mut usage.shared) {
+            let counted_before = match file.inode {
+                Some(inode) if !inodes.insert(inode) => file.allocated,
+                _ => {
+                    let seen: u64 = file
+                        .extents
+                        .iter()
+                        .filter(|(dev, physical, _)| !extents.insert((*dev, *physical)))
+                        .map(|(_, _, length)| length)
+                        .sum();
+                    seen.min(file.allocated)
+                }
+            };
+            usage.subtract(&file, counted_before);
+        }
+    }
+}
+
+impl WorktreeUsage {
+    // 既に数えた分を、worktree全体とファイルを含む内訳から差し引く
+    fn subtract(&mut self, file: &SharedFile, bytes: u64) {
+        let removed = Usage {
+            bytes,
+            ignored_bytes: if file.ignored { bytes } else { 0 },
+        };
+        self.usage.subtract(removed);
+        for &index in &file.entries {
+            self.entries[index].usage.subtract(removed);
+        }
+    }
+}
+
+struct Scan<'a> {
+    root: &'a Path,
+    repo: Option<Repository>,
+    depth: usize,
+    skip: &'a [PathBuf],
+    // reflinkに対応したファイルシステムでのみ共有エクステントを調べる
+    reflinks: bool,
+}
+
+impl Scan<'_> {
+    // openは辿っている途中の内訳（entriesの添字）
+    fn walk(
+        &self,
+        dir: &Path,
+        relative: &Path,
+        level: usize,
+        ignored: bool,
+        result: &mut WorktreeUsage,
+        open: &mut Vec<usize>,
+    ) -> Usage {
+        let mut usage = Usage::default();
+        let Ok(read_dir) = std::fs::read_dir(dir) else { return usage };
+        let mut children: Vec<_> = read_dir.flatten().collect();
+        children.sort_by_key(|entry| entry.file_name());
+
+        for entry in children {
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            let path = entry.path();
+            if path != self.root && self.skip.contains(&path) {
+                continue;
+            }
+            // シンボリックリンクは辿らず、リンク自体を数える
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else { continue };
+            let child_relative = relative.join(entry.file_name());
+            // 無視されたディレクトリの中身は個別に調べない
+            let is_ignored = ignored
+                || self
+                    .repo
+                    .as_ref()
+                    .is_some_and(|repo| repo.is_path_ignored(&child_relative).unwrap_or(false));
+
+            if metadata.is_dir() {
+                // 親を子より先に並べるため、場所を確保してから中身を調べる
+                let index = (level < self.depth).then(|| {
+                    result.entries.push(DirUsage {
+                        path: child_relative.clone(),
+                        depth: level + 1,
+                        usage: Usage::default(),
+                    });
+                    open.push(result.entries.len() - 1);
+                    result.entries.len() - 1
+                });
+                let child = self.walk(&path, &child_relative, level + 1, is_ignored, result, open);
+                if let Some(index) = index {
+                    open.pop();
+                    result.entries[index].usage = child;
+                }
+                usage.add(child);
+            } else {
+                let (bytes, shared) = self.file_usage(&path, &metadata);
+                if let Some(mut shared) = shared {
+                    shared.ignored = is_ignored;
+                    shared.entries = open.clone();
+                    result.shared.push(shared);
+                }
+                usage.bytes += bytes;
+                if is_ignored {
+                    usage.ignored_bytes += bytes;
+                }
+            }
+        }
+        usage
+    }
+
+    // ディスク上で確保されている量と、他と共有している可能性があればその記録
+    #[cfg(unix)]
+    fn file_usage(&self, path: &Path, metadata: &Metadata) -> (u64, Option<SharedFile>) {
+        use std::os::unix::fs::MetadataExt;
+
+        let allocated = metadata.blocks() * 512;
+        let inode = (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()));
+        let extents: Vec<(u64, u64, u64)> = if self.reflinks && metadata.is_file() && allocated > 0 {
+            fiemap::shared_extents(path)
+                .into_iter()
+                .map(|(physical, length)| (metadata.dev(), physical, length))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if inode.is_none() && extents.is_empty() {
+            return (allocated, None);
+        }
+
+        let shared = SharedFile {
+            inode,
+            allocated,
+            extents,
+            ignored: false,
+            entries: Vec::new(),
+        };
+        (allocated, Some(shared))
+    }
+
+    #[cfg(not(unix))]
+    fn file_usage(&self, _path: &Path, metadata: &Metadata) -> (u64, Option<SharedFile>) {
+        (metadata.len(), None)
+    }
+}
+
+// 1536 → 1.5K（duと同じく1024単位）
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size < 10.0 {
+        format!("{:.1}{}", size, UNITS[unit])
+    } else {
+        format!("{:.0}{}", size, UNITS[unit])
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn supports_reflinks(path: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    const BTRFS_SUPER_MAGIC: i64 = 0x9123_683e;
+    const XFS_SUPER_MAGIC: i64 = 0x5846_5342;
+    const BCACHEFS_SUPER_MAGIC: i64 = 0xca45_1a4e;
+
+    let Ok(path) = std::ffi::CString::new(path.as_os_str().as_bytes()) else { return false };
+    // SAFETY: statfsには初期化済みの構造体とNUL終端のパスだけを渡す
+    let fs_type = unsafe {
+        let mut stat: libc::statfs = std::mem::zeroed();
+        if libc::statfs(path.as_ptr(), &mut stat) != 0 {
+            return false;
+        }
+        stat.f_type as i64
+    };
+    matches!(fs_type, BTRFS_SUPER_MAGIC | XFS_SUPER_MAGIC | BCACHEFS_SUPER_MAGIC)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn supports_reflinks(_path: &Path) -> bool {
+    false
+}
+
+// FS_IOC_FIEMAPで他のファイルと共有しているエクステントを取得する（libcに定義がないので自前で持つ）
+#[cfg(target_os = "linux")]
+mod fiemap {
+    use std::os::unix::io::AsRawFd;
+    use std::path::Path;
+
+    // _IOWR('f', 11, struct fiemap)
+    const FS_IOC_FIEMAP: libc::c_ulong = 0xc020_660b;
+    const FIEMAP_EXTENT_LAST: u32 = 0x1;
+    const FIEMAP_EXTENT_SHARED: u32 = 0x2000;
+    const EXTENTS_PER_CALL: usize = 64;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct Extent {
+        logical: u64,
+        physical: u64,
+        length: u64,
+        reserved64: [u64; 2],
+        flags: u32,
+        reserved: [u32; 3],
+    }
+
+    #[repr(C)]
+    struct Request {
+        start: u64,
+        length: u64,
+        flags: u32,
+        mapped_extents: u32,
+        extent_count: u32,
+        reserved: u32,
+        extents: [Extent; EXTENTS_PER_CALL],
+    }
+
+    // (物理オフセット, 長さ)の一覧（取得できない場合は空）
+    pub fn shared_extents(path: &Path) -> Vec<(u64, u64)> {
+        let Ok(file) = std::fs::File::open(path) else { return Vec::new() };
+        let mut shared = Vec::new();
+        let mut start = 0;
+
+        loop {
+            let mut request = Request {
+                start,
+                length: u64::MAX,
+                // 書き込み途中のデータのためにフラッシュはしない（未確定の分は共有として数えないだけ）
+                flags: 0,
+                mapped_extents: 0,
+                extent_count: EXTENTS_PER_CALL as u32,
+                reserved: 0,
+                extents: [Extent::default(); EXTENTS_PER_CALL],
+            };
+            // SAFETY: 要求した数のエクステントを格納できるバッファを渡している
+            if unsafe { libc::ioctl(file.as_raw_fd(), FS_IOC_FIEMAP as _, &mut request) } != 0 {
+                return shared;
+            }
+            let extents = &request.extents[..(request.mapped_extents as usize).min(EXTENTS_PER_CALL)];
+            let Some(last) = extents.last() else { return shared };
+
+            shared.extend(
+                extents
+                    .iter()
+                    .filter(|extent| extent.flags & FIEMAP_EXTENT_SHARED != 0)
+                    .map(|extent| (extent.physical, extent.length)),
+            );
+            if last.flags & FIEMAP_EXTENT_LAST != 0 {
+                return shared;
+            }
+            start = last.logical + last.length;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn setup_git_repo() -> tempfile::TempDir {
+        let temp_dir = tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        init_repo(repo_path);
+        fs::write(repo_path.join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir_all(repo_path.join("src/nested")).unwrap();
+        fs::write(repo_path.join("src/nested/lib.rs"), vec![b'x'; 10_000]).unwrap();
+        fs::create_dir_all(repo_path.join("target/debug")).unwrap();
+        fs::write(repo_path.join("target/debug/app"), vec![b'y'; 50_000]).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_measure_splits_ignored_and_limits_depth() {
+        // What: 無視されたファイルの量が分けて数えられ、--depthまでのディレクトリの内訳が返るかテスト
+        let temp_dir = setup_git_repo();
+        let usage = measure(temp_dir.path(), 1, &[]);
+
+        assert!(usage.usage.ignored_bytes >= 50_000);
+        assert!(usage.usage.bytes - usage.usage.ignored_bytes >= 10_000);
+        let paths: Vec<_> = usage.entries.iter().map(|entry| entry.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("src"), PathBuf::from("target")]);
+        assert_eq!(usage.entries[1].usage.bytes, usage.entries[1].usage.ignored_bytes);
+        assert_eq!(usage.usage.counted(IgnoredMode::Only), usage.usage.ignored_bytes);
+
+        let deeper = measure(temp_dir.path(), 2, &[]);
+        assert!(deeper.entries.iter().any(|entry| entry.path == Path::new("src/nested") && entry.depth == 2));
+        assert_eq!(deeper.usage, usage.usage);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_measure_counts_hardlinks_once_and_skips_nested_worktrees() {
+        // What: ハードリンクされたファイルが一度だけ数えられ、入れ子のworktreeが除かれるかテスト
+        let temp_dir = setup_git_repo();
+        let root = temp_dir.path();
+        let nested = root.join("nested-worktree");
+        fs::create_dir(&nested).unwrap();
+        fs::write(nested.join("big"), vec![b'z'; 100_000]).unwrap();
+        fs::hard_link(root.join("target/debug/app"), root.join("src/app-link")).unwrap();
+
+        let mut with_nested = vec![measure(root, 0, &[])];
+        charge_shared(&mut with_nested);
+        let skip = vec![nested.clone()];
+        let mut usages = vec![measure(root, 0, &skip), measure(&nested, 0, &skip)];
+        charge_shared(&mut usages);
+        let (with_nested, without_nested, nested_usage) = (&with_nested[0], &usages[0], &usages[1]);
+
+        assert!(with_nested.usage.bytes >= without_nested.usage.bytes + 100_000);
+        assert!(without_nested.usage.bytes < 50_000 * 2);
+        assert!(nested_usage.usage.bytes >= 100_000);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_charge_shared_uses_list_order() {
+        // What: worktree間で共有されたファイルが、並び順で先のworktreeとその内訳にだけ数えられるかテスト
+        let temp_dir = setup_git_repo();
+        let root = temp_dir.path();
+        let other = tempdir().unwrap();
+        fs::create_dir(other.path().join("copy")).unwrap();
+        fs::hard_link(root.join("src/nested/lib.rs"), other.path().join("copy/lib.rs")).unwrap();
+        let alone = measure(other.path(), 1, &[]);
+
+        let mut usages = vec![measure(root, 1, &[]), measure(other.path(), 1, &[])];
+        charge_shared(&mut usages);
+        assert_eq!(usages[1].usage.bytes, 0);
+        assert_eq!(usages[1].entries[0].usage.bytes, 0);
+
+        let mut reversed = vec![measure(other.path(), 1, &[]), measure(root, 1, &[])];
+        charge_shared(&mut reversed);
+        assert_eq!((reversed[0].usage, &reversed[0].entries), (alone.usage, &alone.entries));
+        assert_eq!(reversed[1].usage.bytes + alone.usage.bytes, usages[0].usage.bytes);
+        let src = |usage: &WorktreeUsage| usage.entries.iter().find(|e| e.path == Path::new("src")).unwrap().usage;
+        assert_eq!(src(&reversed[1]).bytes + alone.usage.bytes, src(&usages[0]).bytes);
+    }
+
+    #[test]
+    fn test_format_size() {
+        // What: バイト数が1024単位で読みやすく整形されるかテスト
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(1536), "1.5K");
+        assert_eq!(format_size(20 * 1024 * 1024), "20M");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024 + 300 * 1024 * 1024), "3.3G");
+    }
+}
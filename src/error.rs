@@ -21,6 +21,67 @@ pub enum GitGardenerError {
     #[error("Worktree '{name}' not found")]
     WorktreeNotFound { name: String },
     
+    #[error("The main worktree cannot be {action}")]
+    MainWorktree { action: &'static str },
+    
+    #[error("Path '{path}' already exists")]
+    WorktreePathExists { path: String },
+    
+    #[error("Cannot move '{from}' to '{to}': the destination is on a different filesystem")]
+    CrossDeviceMove { from: String, to: String },
+    
+    #[error("Worktree '{name}' has uncommitted changes. Use --force to remove it anyway.")]
+    WorktreeDirty { name: String },
+    
+    #[error("Worktree '{name}' is locked{}", reason.as_ref().map(|reason| format!(": {}", reason)).unwrap_or_default())]
+    WorktreeLocked { name: String, reason: Option<String> },
+    
+    #[error("Worktree '{name}' is not locked")]
+    WorktreeNotLocked { name: String },
+    
+    #[error("Failed to {action} {count} worktree(s)")]
+    WorktreesFailed { action: &'static str, count: usize },
+    
+    #[error("Branch '{name}' does not exist. Use -b flag to create a new branch.")]
+    BranchNotFound { name: String },
+    
+    #[error("Branch '{name}' already exists")]
+    BranchExists { name: String },
+    
+    #[error("Branch '{name}' exists on multiple remotes ({remotes}). Use --remote to choose one.")]
+    AmbiguousRemoteBranch { name: String, remotes: String },
+    
+    #[error("Remote '{name}' not found")]
+    RemoteNotFound { name: String },
+    
+    #[error("Branch '{name}' is already checked out at {path}")]
+    BranchCheckedOut { name: String, path: String },
+    
+    #[error("Invalid revision '{rev}'")]
+    InvalidRevision { rev: String },
+    
+    #[error("Command failed with exit code {}: {command}\n{stderr}", exit_code.map(|code| code.to_string()).unwrap_or_else(|| "(none)".to_string()))]
+    HookFailed {
+        command: String,
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    
+    #[error("Interrupted by {}", crate::signal::signal_name(*signal))]
+    Interrupted { signal: i32 },
+    
+    #[error("Task '{name}' is not defined in .gardener.yml")]
+    TaskNotFound { name: String },
+    
+    #[error("Unknown column '{name}'. Available columns: {available}")]
+    UnknownColumn { name: String, available: String },
+    
+    #[error("Unknown format '{name}'. Use text, json, yaml, tsv, porcelain, a template or one of the formats in .gardener.yml ({available})")]
+    UnknownFormat { name: String, available: String },
+    
+    #[error("Invalid format template '{template}': {message}")]
+    InvalidTemplate { template: String, message: String },
+    
     #[error("Not in a git repository")]
     NotInRepository,
     
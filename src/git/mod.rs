@@ -1,4 +1,5 @@
 pub mod worktree;
 pub mod status;
+pub mod status_cache;
 
 pub use worktree::*;
\ No newline at end of file
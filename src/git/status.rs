@@ -1,9 +1,14 @@
-use git2::{Repository, Status};
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use git2::{Branch, ErrorCode, Oid, Repository, RepositoryState, Status, StatusOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 use crate::error::{GitGardenerError, Result};
 
-// 🔴 RED: Gitワーキングツリーのステータス（まだ基本実装のみ）
-#[derive(Debug, Clone, PartialEq)]
+// Gitワーキングツリーのステータス
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum WorktreeStatus {
     Clean,
     Dirty,
@@ -12,34 +17,159 @@ pub enum WorktreeStatus {
     Diverged,
 }
 
-// 🔴 RED: Gitステータス情報を保持する構造体（まだ基本実装のみ）
-#[derive(Debug, Clone)]
+// Gitステータス情報を保持する構造体
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitStatus {
     pub working_tree_status: WorktreeStatus,
     pub has_staged_changes: bool,
+    // 追跡中のファイルへのステージされていない変更（未追跡ファイルは含まない）
     pub has_unstaged_changes: bool,
+    pub untracked_count: u32,
+    pub conflicted_count: u32,
+    pub renamed_count: u32,
+    pub deleted_count: u32,
+    // 進行中のmerge/rebaseなど
+    pub operation: Option<Operation>,
+    // このworktreeのブランチで作られたstashの数（stash自体は全worktreeで共有される）
+    pub stash_count: u32,
+    pub is_detached: bool,
+    // まだコミットのないブランチ
+    pub is_unborn: bool,
     pub last_commit_time: Option<i64>,
+    // 上流ブランチ（例: origin/main）。設定されていなければNone
+    pub upstream: Option<String>,
+    // 上流ブランチとの差分（上流がなければ0）
     pub ahead_count: u32,
     pub behind_count: u32,
+    // ベースブランチが指定された場合の差分
+    pub base: Option<BaseDivergence>,
+}
+
+// ベースブランチに対してHEADが何コミット進んでいる・遅れているか
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaseDivergence {
+    pub branch: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+// ステータスの取得方法
+#[derive(Debug, Clone)]
+pub struct StatusQuery {
+    // 未追跡ファイルを調べるか（大きなリポジトリでは時間がかかる）
+    pub include_untracked: bool,
+    // ahead/behindを比較するベースブランチ
+    pub base_branch: Option<String>,
+    // サブモジュール内の変更も調べるか（サブモジュールごとにリポジトリを開くので遅い。削除前の確認で使う）
+    pub include_submodules: bool,
+}
+
+impl Default for StatusQuery {
+    fn default() -> Self {
+        Self {
+            include_untracked: true,
+            base_branch: None,
+            include_submodules: false,
+        }
+    }
+}
+
+// 途中で止まっている操作（repo.state()）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl Operation {
+    fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(Operation::Merge),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+                Some(Operation::Rebase)
+            }
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some(Operation::CherryPick),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Operation::Revert),
+            RepositoryState::Bisect => Some(Operation::Bisect),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some(Operation::ApplyMailbox),
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Operation::Merge => "merge",
+            Operation::Rebase => "rebase",
+            Operation::CherryPick => "cherry-pick",
+            Operation::Revert => "revert",
+            Operation::Bisect => "bisect",
+            Operation::ApplyMailbox => "am",
+        };
+        write!(f, "{}", label)
+    }
 }
 
 impl GitStatus {
     pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_path_with_base(path, None)
+    }
+    
+    pub fn from_path_with_base(path: &Path, base_branch: Option<&str>) -> Result<Self> {
+        let query = StatusQuery {
+            base_branch: base_branch.map(|branch| branch.to_string()),
+            ..Default::default()
+        };
+        Self::from_path_with_query(path, &query)
+    }
+    
+    pub fn from_path_with_query(path: &Path, query: &StatusQuery) -> Result<Self> {
         let repo = Repository::open(path).map_err(|e| {
             GitGardenerError::Custom(format!("Failed to open repository: {}", e))
         })?;
         
-        Self::from_repository(&repo)
+        Self::from_repository_with_query(&repo, query)
+    }
+    
+    // worktreeを削除してよいか確かめるためのステータス（サブモジュール内の未コミットの変更も含める）
+    pub fn for_removal(path: &Path) -> Result<Self> {
+        let query = StatusQuery {
+            include_submodules: true,
+            ..Default::default()
+        };
+        Self::from_path_with_query(path, &query)
     }
     
     pub fn from_repository(repo: &Repository) -> Result<Self> {
-        // ワーキングツリーの状態をチェック
-        let statuses = repo.statuses(None).map_err(|e| {
+        Self::from_repository_with_query(repo, &StatusQuery::default())
+    }
+    
+    pub fn from_repository_with_query(repo: &Repository, query: &StatusQuery) -> Result<Self> {
+        let base_branch = query.base_branch.as_deref();
+        
+        // ワーキングツリーの状態をチェック（ステージされたリネームを検出する）
+        // 無視されたファイルは調べず、未追跡のディレクトリは中まで辿らない（`git status`と同じ数え方）
+        // サブモジュールはinclude_submodulesが指定された場合だけ調べる
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(query.include_untracked)
+            .recurse_untracked_dirs(false)
+            .include_ignored(false)
+            .exclude_submodules(!query.include_submodules)
+            .renames_head_to_index(true);
+        let statuses = repo.statuses(Some(&mut options)).map_err(|e| {
             GitGardenerError::Custom(format!("Failed to get repository status: {}", e))
         })?;
         
         let mut has_staged_changes = false;
         let mut has_unstaged_changes = false;
+        let (mut untracked_count, mut conflicted_count, mut renamed_count, mut deleted_count) = (0, 0, 0, 0);
         
         for status_entry in statuses.iter() {
             let flags = status_entry.status();
@@ -51,36 +181,148 @@ impl GitStatus {
             }
             
             if flags.intersects(
-                Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_TYPECHANGE | Status::WT_RENAMED | Status::WT_NEW
+                Status::WT_MODIFIED | Status::WT_DELETED | Status::WT_TYPECHANGE | Status::WT_RENAMED
             ) {
                 has_unstaged_changes = true;
             }
+            
+            if flags.contains(Status::WT_NEW) {
+                untracked_count += 1;
+            }
+            if flags.contains(Status::CONFLICTED) {
+                conflicted_count += 1;
+            }
+            if flags.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+                renamed_count += 1;
+            }
+            if flags.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+                deleted_count += 1;
+            }
         }
         
-        // ワーキングツリーのステータスを決定
-        let working_tree_status = if has_staged_changes || has_unstaged_changes {
-            WorktreeStatus::Dirty
-        } else {
-            WorktreeStatus::Clean
-        };
+        let is_unborn = matches!(repo.head(), Err(e) if e.code() == ErrorCode::UnbornBranch);
+        let is_detached = repo.head_detached().unwrap_or(false);
+        let operation = Operation::from_state(repo.state());
+        let stash_count = Self::get_stash_count(repo)?;
         
         // 最終コミット時刻を取得
         let last_commit_time = Self::get_last_commit_time(repo);
         
-        // 今後の実装: Ahead/Behind の計算
-        let ahead_count = 0;
-        let behind_count = 0;
+        let head_oid = repo.head().ok().and_then(|reference| reference.target());
+        
+        // 上流ブランチとの差分（detached HEADや上流のないブランチは比較しない）
+        let (upstream, ahead_count, behind_count) = match (Self::get_upstream(repo)?, head_oid) {
+            (Some((name, upstream_oid)), Some(head_oid)) => {
+                let (ahead, behind) = repo.graph_ahead_behind(head_oid, upstream_oid)?;
+                (Some(name), ahead as u32, behind as u32)
+            }
+            (upstream, _) => (upstream.map(|(name, _)| name), 0, 0),
+        };
+        
+        let base = match (base_branch, head_oid) {
+            (Some(branch), Some(head_oid)) => Self::get_base_divergence(repo, branch, head_oid)?,
+            _ => None,
+        };
+        
+        // ワーキングツリーのステータスを決定（未コミットの変更を上流との差分より優先する）
+        let working_tree_status = if has_staged_changes || has_unstaged_changes || untracked_count > 0 || conflicted_count > 0 {
+            WorktreeStatus::Dirty
+        } else {
+            match (ahead_count > 0, behind_count > 0) {
+                (true, true) => WorktreeStatus::Diverged,
+                (true, false) => WorktreeStatus::Ahead,
+                (false, true) => WorktreeStatus::Behind,
+                (false, false) => WorktreeStatus::Clean,
+            }
+        };
         
         Ok(GitStatus {
             working_tree_status,
             has_staged_changes,
             has_unstaged_changes,
+            untracked_count,
+            conflicted_count,
+            renamed_count,
+            deleted_count,
+            operation,
+            stash_count,
+            is_detached,
+            is_unborn,
             last_commit_time,
+            upstream,
             ahead_count,
             behind_count,
+            base,
         })
     }
     
+    // HEADのブランチに設定された上流ブランチの名前と先端
+    fn get_upstream(repo: &Repository) -> Result<Option<(String, Oid)>> {
+        let head = match repo.head() {
+            Ok(head) if head.is_branch() => head,
+            // detached HEADや、まだコミットのないブランチ
+            _ => return Ok(None),
+        };
+        
+        match Branch::wrap(head).upstream() {
+            Ok(upstream) => {
+                let name = upstream.name()?.unwrap_or_default().to_string();
+                Ok(upstream.get().target().map(|oid| (name, oid)))
+            }
+            // 上流が設定されていない、または上流のブランチが削除されている
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    // ベースブランチ（ローカルブランチ名、またはorigin/mainなどのリビジョン）との差分
+    fn get_base_divergence(repo: &Repository, branch: &str, head_oid: Oid) -> Result<Option<BaseDivergence>> {
+        let base_oid = match repo.revparse_single(branch).and_then(|object| object.peel_to_commit()) {
+            Ok(commit) => commit.id(),
+            // 存在しない・曖昧・リビジョンとして不正な名前などは、ステータス全体を失敗させず表示しない
+            Err(_) => return Ok(None),
+        };
+        
+        let (ahead, behind) = repo.graph_ahead_behind(head_oid, base_oid)?;
+        Ok(Some(BaseDivergence {
+            branch: branch.to_string(),
+            ahead: ahead as u32,
+            behind: behind as u32,
+        }))
+    }
+    
+    // 複数のworktreeのステータスを並列に取得する
+    // timeoutまでに取得できなかったもの（と取得に失敗したもの）はNoneになる
+    pub fn collect(paths: &[PathBuf], query: &StatusQuery, timeout: Duration) -> Vec<Option<Self>> {
+        let query = query.clone();
+        collect_parallel(paths, Some(timeout), move |path| Self::from_path_with_query(path, &query).ok())
+    }
+    
+    // HEADのブランチで作られたstashの数（refs/stashのreflogのうち、メッセージが
+    // "WIP on <branch>:"または"On <branch>:"のもの。detached HEADでは"(no branch)"）
+    fn get_stash_count(repo: &Repository) -> Result<u32> {
+        let branch = match repo.head() {
+            Ok(_) if repo.head_detached().unwrap_or(false) => "(no branch)".to_string(),
+            Ok(head) => head.shorthand().unwrap_or("").to_string(),
+            Err(_) => return Ok(0),
+        };
+        let prefixes = [format!("WIP on {}:", branch), format!("On {}:", branch)];
+        
+        let reflog = match repo.reflog("refs/stash") {
+            Ok(reflog) => reflog,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let count = reflog
+            .iter()
+            .filter(|entry| {
+                let message = entry.message().unwrap_or("");
+                prefixes.iter().any(|prefix| message.starts_with(prefix.as_str()))
+            })
+            .count();
+        Ok(count as u32)
+    }
+    
     fn get_last_commit_time(repo: &Repository) -> Option<i64> {
         // HEADコミットの時刻を取得
         repo.head().ok()
@@ -88,4 +330,259 @@ impl GitStatus {
             .and_then(|oid| repo.find_commit(oid).ok())
             .map(|commit| commit.time().seconds())
     }
-}
\ No newline at end of file
+}
+
+// pathsごとにfを並列に実行し、結果をpathsの順に返す
+// timeoutを指定すると、それまでに終わらなかったものはNoneになる（遅いworktreeを待たずに返せるよう、スレッドはjoinしない）
+pub(crate) fn collect_parallel<T, F>(paths: &[PathBuf], timeout: Option<Duration>, f: F) -> Vec<Option<T>>
+where
+    T: Send + 'static,
+    F: Fn(&Path) -> Option<T> + Send + Sync + 'static,
+{
+    let mut results: Vec<Option<T>> = paths.iter().map(|_| None).collect();
+    if paths.is_empty() {
+        return results;
+    }
+    
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(paths.len());
+    let paths = Arc::new(paths.to_vec());
+    let next = Arc::new(AtomicUsize::new(0));
+    let f = Arc::new(f);
+    let (sender, receiver) = mpsc::channel();
+    
+    for _ in 0..workers {
+        let (paths, next, sender, f) = (paths.clone(), next.clone(), sender.clone(), f.clone());
+        std::thread::spawn(move || loop {
+            let index = next.fetch_add(1, Ordering::SeqCst);
+            let Some(path) = paths.get(index) else { break };
+            if sender.send((index, f(path))).is_err() {
+                break;
+            }
+        });
+    }
+    drop(sender);
+    
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let received = match deadline {
+            Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                Some(remaining) => receiver.recv_timeout(remaining).ok(),
+                None => None,
+            },
+            None => receiver.recv().ok(),
+        };
+        // タイムアウト、またはすべて取得済み
+        let Some((index, result)) = received else { break };
+        results[index] = result;
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_worktree, commit_all, git, init_repo, setup_git_repo};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn commit(dir: &Path, file: &str) {
+        fs::write(dir.join(file), file).unwrap();
+        commit_all(dir, file);
+    }
+
+    // originをクローンしたリポジトリ（mainが上流を追跡する）を作る
+    fn setup_cloned_repo() -> tempfile::TempDir {
+        let temp_dir = tempdir().unwrap();
+        let origin = temp_dir.path().join("origin");
+        fs::create_dir(&origin).unwrap();
+        init_repo(&origin);
+        commit(&origin, "initial.txt");
+        git(temp_dir.path(), &["clone", "-q", "origin", "clone"]);
+
+        let clone = temp_dir.path().join("clone");
+        git(&clone, &["config", "user.name", "Test User"]);
+        git(&clone, &["config", "user.email", "test@example.com"]);
+        temp_dir
+    }
+
+    #[test]
+    fn test_status_counts_ahead_behind_against_upstream() {
+        // What: 上流ブランチとのahead/behindが数えられ、Ahead/Behind/Divergedが設定されるかテスト
+        let temp_dir = setup_cloned_repo();
+        let (origin, clone) = (temp_dir.path().join("origin"), temp_dir.path().join("clone"));
+
+        let status = GitStatus::from_path(&clone).unwrap();
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!(status.working_tree_status, WorktreeStatus::Clean);
+
+        commit(&clone, "local.txt");
+        let status = GitStatus::from_path(&clone).unwrap();
+        assert_eq!((status.ahead_count, status.behind_count), (1, 0));
+        assert_eq!(status.working_tree_status, WorktreeStatus::Ahead);
+
+        commit(&origin, "remote-1.txt");
+        commit(&origin, "remote-2.txt");
+        git(&clone, &["fetch", "-q"]);
+        let status = GitStatus::from_path(&clone).unwrap();
+        assert_eq!((status.ahead_count, status.behind_count), (1, 2));
+        assert_eq!(status.working_tree_status, WorktreeStatus::Diverged);
+
+        git(&clone, &["reset", "-q", "--hard", "HEAD~1"]);
+        let status = GitStatus::from_path(&clone).unwrap();
+        assert_eq!(status.working_tree_status, WorktreeStatus::Behind);
+
+        // 未コミットの変更は上流との差分より優先される
+        fs::write(clone.join("scratch.txt"), "scratch").unwrap();
+        assert_eq!(GitStatus::from_path(&clone).unwrap().working_tree_status, WorktreeStatus::Dirty);
+    }
+
+    #[test]
+    fn test_status_without_upstream_compares_against_base() {
+        // What: 上流のないブランチではupstreamがNoneになり、ベースブランチとの差分が数えられるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        git(&clone, &["checkout", "-q", "-b", "feature"]);
+        commit(&clone, "feature-1.txt");
+        commit(&clone, "feature-2.txt");
+
+        let status = GitStatus::from_path_with_base(&clone, Some("main")).unwrap();
+
+        assert!(status.upstream.is_none());
+        assert_eq!((status.ahead_count, status.behind_count), (0, 0));
+        assert_eq!(status.working_tree_status, WorktreeStatus::Clean);
+        assert_eq!(status.base, Some(BaseDivergence { branch: "main".to_string(), ahead: 2, behind: 0 }));
+        assert!(GitStatus::from_path_with_base(&clone, Some("missing")).unwrap().base.is_none());
+        assert!(GitStatus::from_path_with_base(&clone, Some("main..")).unwrap().base.is_none());
+        assert!(GitStatus::from_path_with_base(&clone, Some("main^{tree}")).unwrap().base.is_none());
+    }
+
+    #[test]
+    fn test_stash_count_is_per_branch() {
+        // What: stashが作られたブランチのworktreeでだけ数えられるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        let other = add_worktree(repo_path, "feature/other", "other");
+        fs::write(other.join("README.md"), "changed").unwrap();
+        git(&other, &["stash", "-q"]);
+        fs::write(other.join("README.md"), "changed again").unwrap();
+        git(&other, &["stash", "push", "-q", "-m", "named"]);
+
+        assert_eq!(GitStatus::from_path(&other).unwrap().stash_count, 2);
+        assert_eq!(GitStatus::from_path(repo_path).unwrap().stash_count, 0);
+    }
+
+    #[test]
+    fn test_status_counts_untracked_renamed_deleted_and_stashes() {
+        // What: 未追跡・リネーム・削除されたファイルとstashが区別して数えられるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        commit(&clone, "a.txt");
+        commit(&clone, "b.txt");
+        fs::write(clone.join("stashed.txt"), "stashed").unwrap();
+        git(&clone, &["add", "stashed.txt"]);
+        git(&clone, &["stash", "-q"]);
+
+        fs::write(clone.join("untracked.txt"), "untracked").unwrap();
+        git(&clone, &["mv", "a.txt", "renamed.txt"]);
+        fs::remove_file(clone.join("b.txt")).unwrap();
+
+        let status = GitStatus::from_path(&clone).unwrap();
+
+        assert_eq!(status.untracked_count, 1);
+        assert_eq!(status.renamed_count, 1);
+        assert_eq!(status.deleted_count, 1);
+        assert_eq!(status.stash_count, 1);
+        assert!(status.has_staged_changes);
+        assert!(status.has_unstaged_changes);
+        assert_eq!(status.working_tree_status, WorktreeStatus::Dirty);
+        assert!(!status.is_detached && !status.is_unborn);
+    }
+
+    #[test]
+    fn test_status_untracked_only_is_not_unstaged() {
+        // What: 未追跡ファイルだけの場合はunstagedとして扱わないが、Dirtyにはなるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        fs::write(clone.join("untracked.txt"), "untracked").unwrap();
+
+        let status = GitStatus::from_path(&clone).unwrap();
+
+        assert!(!status.has_unstaged_changes);
+        assert_eq!(status.untracked_count, 1);
+        assert_eq!(status.working_tree_status, WorktreeStatus::Dirty);
+    }
+
+    #[test]
+    fn test_status_detects_conflicted_merge() {
+        // What: 衝突したmergeの途中で、衝突の数と進行中の操作が検出されるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        git(&clone, &["checkout", "-q", "-b", "other"]);
+        fs::write(clone.join("initial.txt"), "other").unwrap();
+        git(&clone, &["commit", "-q", "-am", "other"]);
+        git(&clone, &["checkout", "-q", "main"]);
+        fs::write(clone.join("initial.txt"), "main").unwrap();
+        git(&clone, &["commit", "-q", "-am", "main"]);
+        git(&clone, &["merge", "other"]);
+
+        let status = GitStatus::from_path(&clone).unwrap();
+
+        assert_eq!(status.conflicted_count, 1);
+        assert_eq!(status.operation, Some(Operation::Merge));
+        assert_eq!(status.working_tree_status, WorktreeStatus::Dirty);
+    }
+
+    #[test]
+    fn test_status_flags_detached_and_unborn_head() {
+        // What: detached HEADと、コミットのないブランチが検出されるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        git(&clone, &["checkout", "-q", "--detach"]);
+        let detached = GitStatus::from_path(&clone).unwrap();
+
+        let empty = temp_dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+        git(&empty, &["init", "-q"]);
+        let unborn = GitStatus::from_path(&empty).unwrap();
+
+        assert!(detached.is_detached);
+        assert!(detached.upstream.is_none());
+        assert!(unborn.is_unborn);
+        assert!(unborn.last_commit_time.is_none());
+        assert_eq!(unborn.working_tree_status, WorktreeStatus::Clean);
+    }
+
+    #[test]
+    fn test_collect_statuses_in_parallel() {
+        // What: 複数のworktreeのステータスが並列に取得され、取得できないものはNoneになるかテスト
+        let temp_dir = setup_cloned_repo();
+        let clone = temp_dir.path().join("clone");
+        fs::write(clone.join("untracked.txt"), "untracked").unwrap();
+        let paths = vec![temp_dir.path().join("origin"), clone.clone(), temp_dir.path().join("missing")];
+
+        let statuses = GitStatus::collect(&paths, &StatusQuery::default(), Duration::from_secs(10));
+
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Clean);
+        assert_eq!(statuses[1].as_ref().unwrap().untracked_count, 1);
+        assert!(statuses[2].is_none());
+
+        // 未追跡ファイルを調べない場合はクリーンとみなす
+        let query = StatusQuery { include_untracked: false, ..Default::default() };
+        let statuses = GitStatus::collect(&paths[1..2], &query, Duration::from_secs(10));
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Clean);
+    }
+
+    #[test]
+    fn test_collect_statuses_times_out() {
+        // What: タイムアウトまでに取得できなかったステータスはNoneになるかテスト
+        let temp_dir = setup_cloned_repo();
+        let paths = vec![temp_dir.path().join("clone")];
+
+        let statuses = GitStatus::collect(&paths, &StatusQuery::default(), Duration::ZERO);
+
+        assert!(statuses[0].is_none());
+    }
+}
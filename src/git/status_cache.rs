@@ -0,0 +1,423 @@
+use git2::{Branch, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use crate::error::{GitGardenerError, Result};
+use crate::state::write_atomically;
+use super::status::{collect_parallel, GitStatus, StatusQuery};
+use super::worktree::common_dir;
+
+// worktreeごとのステータスのキャッシュ（<git common dir>/gardener/status.yml）
+pub struct StatusCache {
+    state_dir: PathBuf,
+    entries: BTreeMap<PathBuf, StatusCacheEntry>,
+    // trueならキャッシュを使わずに取得し直す
+    refresh: bool,
+    // trueならタイムアウトせずにすべてのworktreeを取得する
+    wait: bool,
+    changed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct StatusCacheFile {
+    #[serde(default)]
+    worktrees: BTreeMap<PathBuf, StatusCacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusCacheEntry {
+    key: StatusKey,
+    status: GitStatus,
+}
+
+// キャッシュが有効かどうかを決める値（どれかが変わればステータスを取得し直す）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusKey {
+    // indexファイルの更新時刻（ナノ秒）
+    pub index_mtime: Option<u128>,
+    // HEADのref名とコミット、上流ブランチ・ベースブランチの先端
+    pub refs: Vec<String>,
+    // refs/stashのreflogのサイズ（stashの追加・削除で変わる。ファイルがなければ0）
+    pub stash_log_size: u64,
+    // マージ・rebaseなどの進行中の操作
+    pub state: String,
+    // ワーキングツリー内（.gitと無視されたディレクトリを除く）の各ディレクトリの更新時刻（ナノ秒）
+    // gitのuntracked cacheと同じく、ファイルの作成・削除・名前の変更はディレクトリの更新時刻で検出する
+    pub dirs: BTreeMap<PathBuf, u128>,
+    pub include_untracked: bool,
+    #[serde(default)]
+    pub include_submodules: bool,
+    pub base_branch: Option<String>,
+}
+
+impl StatusKey {
+    // ステータスを取得する前に計算する（取得中の変更は次回のキャッシュミスになる）
+    pub fn compute(path: &Path, query: &StatusQuery) -> Option<Self> {
+        let repo = Repository::open(path).ok()?;
+        let workdir = repo.workdir()?.to_path_buf();
+
+        Some(Self {
+            dirs: dir_mtimes(&repo, &workdir),
+            ..Self::compute_without_dirs(&repo, query)
+        })
+    }
+
+    // キャッシュが今も有効か（ワーキングツリーは辿らず、記録したディレクトリだけをstatする）
+    pub fn is_current(&self, path: &Path, query: &StatusQuery) -> bool {
+        let Ok(repo) = Repository::open(path) else {
+            return false;
+        };
+        let Some(workdir) = repo.workdir() else {
+            return false;
+        };
+
+        let key = Self::compute_without_dirs(&repo, query);
+        key.index_mtime == self.index_mtime
+            && key.refs == self.refs
+            && key.stash_log_size == self.stash_log_size
+            && key.state == self.state
+            && key.include_untracked == self.include_untracked
+            && key.include_submodules == self.include_submodules
+            && key.base_branch == self.base_branch
+            && self.dirs.iter().all(|(relative, mtime)| {
+                std::fs::metadata(workdir.join(relative)).ok().and_then(|m| mtime_nanos(&m)) == Some(*mtime)
+            })
+            && index_matches_workdir(&repo, workdir, self.index_mtime)
+    }
+
+    fn compute_without_dirs(repo: &Repository, query: &StatusQuery) -> Self {
+        Self {
+            index_mtime: std::fs::metadata(repo.path().join("index")).ok().and_then(|m| mtime_nanos(&m)),
+            refs: ref_targets(repo, query.base_branch.as_deref()),
+            stash_log_size: std::fs::metadata(common_dir(repo).join("logs/refs/stash")).map(|m| m.len()).unwrap_or(0),
+            state: format!("{:?}", repo.state()),
+            dirs: BTreeMap::new(),
+            include_untracked: query.include_untracked,
+            include_submodules: query.include_submodules,
+            base_branch: query.base_branch.clone(),
+        }
+    }
+}
+
+impl StatusCache {
+    pub fn load(state_dir: &Path) -> Result<Self> {
+        let path = Self::cache_path(state_dir);
+        let entries = if path.exists() {
+            let contents = std::fs::read_to_string(&path)?;
+            // 壊れたキャッシュは捨てて作り直す
+            serde_yaml::from_str::<StatusCacheFile>(&contents)
+                .map(|file| file.worktrees)
+                .unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            state_dir: state_dir.to_path_buf(),
+            entries,
+            refresh: false,
+            wait: false,
+            changed: false,
+        })
+    }
+
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn wait(mut self, wait: bool) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    pub fn cache_path(state_dir: &Path) -> PathBuf {
+        state_dir.join("status.yml")
+    }
+
+    // 変更があった場合のみ保存する（存在しなくなったworktreeの記録は捨てる）
+    pub fn save(&mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        self.entries.retain(|path, _| path.exists());
+
+        let file = StatusCacheFile { worktrees: self.entries.clone() };
+        let contents = serde_yaml::to_string(&file)
+            .map_err(|e| GitGardenerError::Custom(format!("Failed to serialize status cache: {}", e)))?;
+        write_atomically(&Self::cache_path(&self.state_dir), &contents)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    // キャッシュが有効なworktreeはキャッシュから、それ以外は並列に取得する
+    // refresh時はタイムアウトせず、すべてのworktreeを取得し直す（wait時はタイムアウトだけしない）
+    pub fn collect(&mut self, paths: &[PathBuf], query: &StatusQuery, timeout: Duration) -> Vec<Option<GitStatus>> {
+        let entries = std::sync::Arc::new(self.entries.clone());
+        let (query, refresh) = (query.clone(), self.refresh);
+        let timeout = if refresh || self.wait { None } else { Some(timeout) };
+
+        let results = collect_parallel(paths, timeout, move |path| {
+            if let Some(entry) = entries.get(path).filter(|entry| !refresh && entry.key.is_current(path, &query)) {
+                return Some((entry.status.clone(), None));
+            }
+            let key = StatusKey::compute(path, &query);
+            let status = GitStatus::from_path_with_query(path, &query).ok()?;
+            Some((status, key))
+        });
+
+        paths
+            .iter()
+            .zip(results)
+            .map(|(path, result)| {
+                let (status, key) = result?;
+                if let Some(key) = key {
+                    self.entries.insert(path.clone(), StatusCacheEntry { key, status: status.clone() });
+                    self.changed = true;
+                }
+                Some(status)
+            })
+            .collect()
+    }
+}
+
+fn mtime_nanos(metadata: &std::fs::Metadata) -> Option<u128> {
+    metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_nanos())
+}
+
+fn ref_targets(repo: &Repository, base_branch: Option<&str>) -> Vec<String> {
+    let mut refs = Vec::new();
+    if let Ok(head) = repo.head() {
+        refs.push(head.name().unwrap_or("HEAD").to_string());
+        refs.push(head.target().map(|oid| oid.to_string()).unwrap_or_default());
+        if head.is_branch() {
+            let upstream = Branch::wrap(head).upstream().ok().and_then(|upstream| upstream.get().target());
+            refs.push(upstream.map(|oid| oid.to_string()).unwrap_or_default());
+        }
+    }
+    if let Some(branch) = base_branch {
+        let base = repo.revparse_single(branch).ok().map(|object| object.id().to_string());
+        refs.push(base.unwrap_or_default());
+    }
+    refs
+}
+
+// ワーキングツリーのディレクトリを辿り、それぞれの更新時刻をworkdirからの相対パスで返す
+// （ファイルはstatしないので、キャッシュミスのときもstatusよりずっと軽い）
+// 追跡中のファイルがindexに記録されたstat情報（更新時刻・サイズ・inode）と一致するか
+// ファイルをその場で書き換えてもディレクトリの更新時刻は変わらないので、gitと同じくファイルごとに確かめる
+fn index_matches_workdir(repo: &Repository, workdir: &Path, index_mtime: Option<u128>) -> bool {
+    // サブモジュール（gitlink）はディレクトリなので比べない
+    const GITLINK_MODE: u32 = 0o160000;
+
+    let Ok(index) = repo.index() else {
+        return false;
+    };
+    index.iter().all(|entry| {
+        if entry.mode == GITLINK_MODE {
+            return true;
+        }
+        let Ok(metadata) = std::fs::symlink_metadata(workdir.join(index_entry_path(&entry.path))) else {
+            return false;
+        };
+        let Some(mtime) = mtime_nanos(&metadata) else {
+            return false;
+        };
+        // indexと同時かそれ以降に更新されたファイルは、stat情報が同じでも内容が変わりうる（racy git）
+        if index_mtime.map_or(true, |index_mtime| mtime >= index_mtime) {
+            return false;
+        }
+
+        // ナノ秒やinodeを記録しない実装もあるので、0の場合は比べない（サイズは下位32ビットだけが記録される）
+        let seconds_match = mtime / 1_000_000_000 == u128::from(entry.mtime.seconds() as u32);
+        let nanos_match = entry.mtime.nanoseconds() == 0
+            || mtime % 1_000_000_000 == u128::from(entry.mtime.nanoseconds());
+        let size_match = entry.file_size == metadata.len() as u32;
+        seconds_match && nanos_match && size_match && inode_matches(entry.ino, &metadata)
+    })
+}
+
+#[cfg(unix)]
+fn index_entry_path(path: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(path))
+}
+
+#[cfg(not(unix))]
+fn index_entry_path(path: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(path).into_owned())
+}
+
+#[cfg(unix)]
+fn inode_matches(ino: u32, metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    ino == 0 || ino == metadata.ino() as u32
+}
+
+#[cfg(not(unix))]
+fn inode_matches(_ino: u32, _metadata: &std::fs::Metadata) -> bool {
+    true
+}
+
+fn dir_mtimes(repo: &Repository, workdir: &Path) -> BTreeMap<PathBuf, u128> {
+    let mut dirs = BTreeMap::new();
+    let mut pending = vec![workdir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Some(mtime) = std::fs::metadata(&dir).ok().and_then(|m| mtime_nanos(&m)) else { continue };
+        let relative = dir.strip_prefix(workdir).unwrap_or(&dir).to_path_buf();
+        dirs.insert(relative, mtime);
+
+        let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            if entry.file_name() == ".git" || !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let path = entry.path();
+            let ignored = path
+                .strip_prefix(workdir)
+                .ok()
+                .is_some_and(|relative| repo.is_path_ignored(relative).unwrap_or(false));
+            if !ignored {
+                pending.push(path);
+            }
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_all, git, init_repo};
+    use crate::git::status::WorktreeStatus;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn setup_git_repo() -> tempfile::TempDir {
+        let temp_dir = tempdir().unwrap();
+        let repo_path = temp_dir.path().join("repo");
+        fs::create_dir(&repo_path).unwrap();
+        init_repo(&repo_path);
+        fs::create_dir(repo_path.join("src")).unwrap();
+        fs::write(repo_path.join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(repo_path.join(".gitignore"), "target/\n").unwrap();
+        commit_all(&repo_path, "Initial commit");
+        temp_dir
+    }
+
+    #[test]
+    fn test_status_key_changes_with_files_and_head() {
+        // What: ファイルの追加・コミット・stash・マージの開始でキーが変わり、無視されたディレクトリの変更では変わらないかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path().join("repo");
+        let query = StatusQuery::default();
+        let key = || StatusKey::compute(&repo_path, &query).unwrap();
+
+        let initial = key();
+        assert_eq!(initial, key());
+        assert!(initial.is_current(&repo_path, &query));
+
+        fs::create_dir(repo_path.join("target")).unwrap();
+        fs::write(repo_path.join("target/out"), "build").unwrap();
+        let after_build = key();
+        assert!(!initial.is_current(&repo_path, &query));
+        // targetディレクトリの作成でルートのmtimeは変わるが、中身の変更は見ない
+        fs::write(repo_path.join("target/new"), "rebuild").unwrap();
+        assert_eq!(after_build, key());
+
+        std::thread::sleep(Duration::from_millis(10));
+        fs::write(repo_path.join("src/new.rs"), "fn new() {}").unwrap();
+        let added = key();
+        assert_ne!(after_build.dirs, added.dirs);
+        assert!(!after_build.is_current(&repo_path, &query));
+
+        git(&repo_path, &["add", "."]);
+        git(&repo_path, &["commit", "-q", "-m", "Add"]);
+        let committed = key();
+        assert_ne!(added.refs, committed.refs);
+        assert_ne!(added.index_mtime, committed.index_mtime);
+
+        fs::write(repo_path.join("src/lib.rs"), "fn main() { edited() }").unwrap();
+        git(&repo_path, &["stash", "-q"]);
+        let stashed = key();
+        assert_eq!(committed.stash_log_size, 0);
+        assert!(stashed.stash_log_size > 0);
+
+        let head = git(&repo_path, &["rev-parse", "HEAD"]).stdout;
+        fs::write(repo_path.join(".git/MERGE_HEAD"), head).unwrap();
+        assert_ne!(stashed.state, key().state);
+        assert!(!stashed.is_current(&repo_path, &query));
+    }
+
+    #[test]
+    fn test_status_cache_detects_in_place_edit() {
+        // What: ディレクトリの更新時刻が変わらない、追跡中のファイルのその場での書き換えでキャッシュが無効になるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path().join("repo");
+        let state_dir = temp_dir.path().join("state");
+        let paths = vec![repo_path.clone()];
+        let query = StatusQuery::default();
+        let timeout = Duration::from_secs(10);
+
+        let mut cache = StatusCache::load(&state_dir).unwrap();
+        let statuses = cache.collect(&paths, &query, timeout);
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Clean);
+        let src_mtime = fs::metadata(repo_path.join("src")).unwrap().modified().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        // 同じ長さの内容で書き換えて、サイズではなく更新時刻で検出されることも確かめる
+        fs::write(repo_path.join("src/lib.rs"), "fn mine() {}").unwrap();
+        assert_eq!(fs::metadata(repo_path.join("src")).unwrap().modified().unwrap(), src_mtime);
+
+        let statuses = cache.collect(&paths, &query, timeout);
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Dirty);
+    }
+
+    #[test]
+    fn test_status_cache_reuses_and_refreshes_entries() {
+        // What: キーが同じ間はキャッシュが使われ、変更や--refreshで取得し直されるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path().join("repo");
+        let state_dir = temp_dir.path().join("state");
+        let paths = vec![repo_path.clone()];
+        let query = StatusQuery::default();
+        let timeout = Duration::from_secs(10);
+
+        let mut cache = StatusCache::load(&state_dir).unwrap();
+        let statuses = cache.collect(&paths, &query, timeout);
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Clean);
+        cache.save().unwrap();
+        assert!(StatusCache::cache_path(&state_dir).exists());
+
+        // キャッシュを書き換えて、キーが同じならそれが返されることを確かめる
+        let mut cache = StatusCache::load(&state_dir).unwrap();
+        cache.entries.get_mut(&repo_path).unwrap().status.stash_count = 7;
+        assert_eq!(cache.collect(&paths, &query, timeout)[0].as_ref().unwrap().stash_count, 7);
+
+        let mut refreshed = StatusCache::load(&state_dir).unwrap().refresh(true);
+        refreshed.entries.get_mut(&repo_path).unwrap().status.stash_count = 7;
+        assert_eq!(refreshed.collect(&paths, &query, timeout)[0].as_ref().unwrap().stash_count, 0);
+
+        std::thread::sleep(Duration::from_millis(10));
+        fs::write(repo_path.join("src/new.rs"), "fn new() {}").unwrap();
+        let statuses = cache.collect(&paths, &query, timeout);
+        assert_eq!(statuses[0].as_ref().unwrap().working_tree_status, WorktreeStatus::Dirty);
+    }
+
+    #[test]
+    fn test_status_cache_wait_ignores_timeout() {
+        // What: waitを指定するとタイムアウトを過ぎてもすべてのworktreeのステータスを取得するかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path().join("repo");
+        let state_dir = temp_dir.path().join("state");
+        let paths = vec![repo_path.clone()];
+        let query = StatusQuery::default();
+
+        let mut timed_out = StatusCache::load(&state_dir).unwrap();
+        assert!(timed_out.collect(&paths, &query, Duration::ZERO)[0].is_none());
+
+        let mut waited = StatusCache::load(&state_dir).unwrap().wait(true);
+        assert!(waited.collect(&paths, &query, Duration::ZERO)[0].is_some());
+    }
+}
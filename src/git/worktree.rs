@@ -1,131 +1,331 @@
-use git2::{Repository, BranchType};
+use git2::{BranchType, Cred, CredentialType, ErrorCode, FetchOptions, Oid, RemoteCallbacks, Repository, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use crate::error::{GitGardenerError, Result};
-use super::status::{GitStatus, WorktreeStatus};
+use super::status::{GitStatus, StatusQuery, WorktreeStatus};
+use super::status_cache::StatusCache;
 
-#[derive(Clone)]
-pub struct WorktreeInfo {
+// リモートにだけ存在するブランチ（refs/remotes/<remote>/<name>）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteBranch {
+    pub remote: String,
     pub name: String,
-    pub path: PathBuf,
-    pub branch: String,
-    pub is_prunable: bool,
-    pub status: Option<GitStatus>,
 }
 
-// 🔴 RED: ステータス情報付きWorktreeInfo（まだ基本実装のみ）
+impl RemoteBranch {
+    // `origin/feature-x`の形式の名前
+    pub fn shorthand(&self) -> String {
+        format!("{}/{}", self.remote, self.name)
+    }
+}
+
+// ブランチがベースブランチに取り込まれた方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeMethod {
+    // ブランチの先端がベースの祖先（通常のマージ・fast-forward）
+    Ancestor,
+    // ブランチの各コミットと同じ変更がベースにある
+    Rebase,
+    // ブランチの変更全体がベースに含まれている
+    Squash,
+}
+
+impl std::fmt::Display for MergeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            MergeMethod::Ancestor => "merge",
+            MergeMethod::Rebase => "rebase",
+            MergeMethod::Squash => "squash",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// コミットの概要（listの表示用）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitSummary {
+    pub short_id: String,
+    pub subject: String,
+    pub time: i64,
+}
+
+// メインworktreeの名前（`cd @`などで使うエイリアス）
+pub const MAIN_WORKTREE_NAME: &str = "@";
+
 #[derive(Clone)]
-pub struct WorktreeInfoWithStatus {
+pub struct WorktreeInfo {
     pub name: String,
     pub path: PathBuf,
     pub branch: String,
     pub is_prunable: bool,
-    pub status: GitStatus,
+    pub is_main: bool,
+    pub is_current: bool,
+    pub is_detached: bool,
+    pub is_locked: bool,
+    pub lock_reason: Option<String>,
+    // HEADのコミット（ディレクトリが失われている場合などはNone）
+    pub head: Option<Oid>,
+    // list_worktreesでは取得しない（必要な場合にload_statusesで埋める）
+    pub status: Option<GitStatus>,
 }
 
 pub struct GitWorktree {
     repo: Repository,
+    // コミットごとのpatch-id（cleanで複数のブランチを調べるとき、ベース側の差分を計算し直さない）
+    patch_ids: RefCell<HashMap<Oid, Option<Oid>>>,
 }
 
 impl GitWorktree {
     pub fn new() -> Result<Self> {
         let repo = Repository::open_from_env()
             .map_err(|_| GitGardenerError::NotInRepository)?;
-        Ok(Self { repo })
+        Ok(Self { repo, patch_ids: RefCell::default() })
     }
     
     pub fn from_path(path: &Path) -> Result<Self> {
         let repo = Repository::open(path)
             .map_err(|_| GitGardenerError::NotInRepository)?;
-        Ok(Self { repo })
+        Ok(Self { repo, patch_ids: RefCell::default() })
     }
     
     pub fn create_worktree(
         &self,
-        _name: &str,
+        name: &str,
         path: &Path,
         branch_name: &str,
         create_branch: bool,
     ) -> Result<()> {
-        // 🟢 GREEN: worktree作成の修正版
-        if create_branch && !self.branch_exists(branch_name)? {
-            // 新しいブランチを作成
-            let head = self.repo.head()?;
-            let commit = head.peel_to_commit()?;
-            let _branch = self.repo.branch(branch_name, &commit, false)?;
-        }
-        
-        // gitコマンドを使用してworktreeを作成（より安定した方法）
-        let output = std::process::Command::new("git")
-            .args(&["worktree", "add", &path.to_string_lossy(), branch_name])
-            .output()
-            .map_err(|e| GitGardenerError::Custom(format!("Failed to execute git worktree add: {}", e)))?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(GitGardenerError::Custom(format!("git worktree add failed: {}", error_msg)));
-        }
-        
-        Ok(())
+        self.create_worktree_with_commit(name, path, branch_name, create_branch, None)
     }
     
     pub fn create_worktree_with_commit(
         &self,
-        _name: &str,
+        name: &str,
         path: &Path,
         branch_name: &str,
         create_branch: bool,
         commit: Option<&str>,
     ) -> Result<()> {
-        // gitコマンドを使用してworktreeを作成
-        let mut args = vec!["worktree", "add"];
+        if path.exists() {
+            return Err(GitGardenerError::WorktreePathExists {
+                path: path.display().to_string(),
+            });
+        }
         
-        if create_branch {
-            args.push("-b");
-            args.push(branch_name);
+        let reference = if create_branch {
+            if self.branch_exists(branch_name)? {
+                return Err(GitGardenerError::BranchExists { name: branch_name.to_string() });
+            }
+            
+            // 起点のコミット（未指定ならHEAD）から新しいブランチを作成
+            let target = match commit {
+                Some(rev) => self.resolve_commit(rev)?,
+                None => self.repo.head()?.peel_to_commit()?,
+            };
+            self.repo.branch(branch_name, &target, false)?.into_reference()
+        } else {
+            let branch = self.repo
+                .find_branch(branch_name, BranchType::Local)
+                .map_err(|_| GitGardenerError::BranchNotFound { name: branch_name.to_string() })?;
+            
+            // 同じブランチを複数のworktreeでチェックアウトすることはできない
+            if let Some(checked_out) = self.branch_checked_out_at(branch_name) {
+                return Err(GitGardenerError::BranchCheckedOut {
+                    name: branch_name.to_string(),
+                    path: checked_out.display().to_string(),
+                });
+            }
+            branch.into_reference()
+        };
+        
+        let mut options = WorktreeAddOptions::new();
+        options.reference(Some(&reference));
+        
+        let admin_name = self.unique_worktree_name(name);
+        if let Err(e) = self.repo.worktree(&admin_name, path, Some(&options)) {
+            // 作成に失敗したら、このために作ったブランチは残さない
+            if create_branch {
+                let _ = self.delete_branch(branch_name);
+            }
+            
+            return Err(worktree_add_error(e, path));
         }
         
-        let path_str = path.to_string_lossy();
-        args.push(&path_str);
+        Ok(())
+    }
+    
+    // --detachで指定されたリビジョンを解決し、コミットとディレクトリ名を返す
+    // （タグやブランチなら参照の短縮名、それ以外は`detached-<sha7>`）
+    pub fn resolve_detach_target(&self, rev: &str) -> Result<(Oid, String)> {
+        let invalid = |_| GitGardenerError::InvalidRevision { rev: rev.to_string() };
+        let (object, reference) = self.repo.revparse_ext(rev).map_err(invalid)?;
+        let commit = object.peel_to_commit().map_err(invalid)?;
+        
+        let name = reference
+            .filter(|reference| reference.name() != Some("HEAD"))
+            .and_then(|reference| reference.shorthand().map(|name| name.to_string()))
+            .unwrap_or_else(|| format!("detached-{}", short_id(commit.id())));
         
-        if !create_branch {
-            args.push(branch_name);
-        } else if let Some(commit) = commit {
-            args.push(commit);
+        Ok((commit.id(), name))
+    }
+    
+    pub fn create_detached_worktree(&self, name: &str, path: &Path, oid: Oid) -> Result<()> {
+        if path.exists() {
+            return Err(GitGardenerError::WorktreePathExists {
+                path: path.display().to_string(),
+            });
         }
         
-        let output = std::process::Command::new("git")
-            .args(&args)
-            .output()
-            .map_err(|e| GitGardenerError::Custom(format!("Failed to execute git worktree add: {}", e)))?;
+        // libgit2は参照なしだとworktree名のブランチを作ってしまうため、
+        // 一時ブランチでチェックアウトしてからHEADを切り離し、一時ブランチを削除する
+        let commit = self.repo.find_commit(oid)?;
+        let admin_name = self.unique_worktree_name(name);
+        // 既存のブランチを上書きしないよう、使われていない名前が見つかるまで連番を付ける
+        let base_branch = format!("gardener-detach/{}", admin_name);
+        let mut temp_branch = base_branch.clone();
+        let mut suffix = 1;
+        let reference = loop {
+            match self.repo.branch(&temp_branch, &commit, false) {
+                Ok(branch) => break branch.into_reference(),
+                Err(e) if e.code() == ErrorCode::Exists => {
+                    temp_branch = format!("{}-{}", base_branch, suffix);
+                    suffix += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
         
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(GitGardenerError::Custom(format!("git worktree add failed: {}", error_msg)));
+        let mut options = WorktreeAddOptions::new();
+        options.reference(Some(&reference));
+        let result = self.repo
+            .worktree(&admin_name, path, Some(&options))
+            .map_err(|e| worktree_add_error(e, path))
+            .and_then(|worktree| {
+                let detached = Repository::open_from_worktree(&worktree)
+                    .and_then(|worktree_repo| worktree_repo.set_head_detached(oid));
+                if let Err(e) = detached {
+                    let mut prune_options = WorktreePruneOptions::new();
+                    prune_options.valid(true).working_tree(true);
+                    let _ = worktree.prune(Some(&mut prune_options));
+                    return Err(GitGardenerError::Git(e));
+                }
+                Ok(())
+            });
+        
+        drop(reference);
+        let _ = self.delete_branch(&temp_branch);
+        result
+    }
+    
+    // リビジョン（SHA、タグ、ブランチなど）をコミットに解決する
+    fn resolve_commit(&self, rev: &str) -> Result<git2::Commit<'_>> {
+        self.repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|_| GitGardenerError::InvalidRevision { rev: rev.to_string() })
+    }
+    
+    // `.git/worktrees/<name>`に使う名前（'/'は使えないので置き換え、重複時は連番を付ける）
+    fn unique_worktree_name(&self, name: &str) -> String {
+        let base = sanitize_worktree_name(name);
+        let worktrees_dir = self.get_common_dir().join("worktrees");
+        
+        let mut candidate = base.clone();
+        let mut suffix = 1;
+        while worktrees_dir.join(&candidate).exists() {
+            candidate = format!("{}{}", base, suffix);
+            suffix += 1;
         }
         
-        Ok(())
+        candidate
+    }
+    
+    // ブランチをチェックアウトしているworktree（メインを含む）のパス
+    fn branch_checked_out_at(&self, branch_name: &str) -> Option<PathBuf> {
+        let target = format!("refs/heads/{}", branch_name);
+        let mut repos = Vec::new();
+        
+        if let Ok(main) = Repository::open(self.get_common_dir()) {
+            repos.push(main);
+        }
+        if let Ok(names) = self.repo.worktrees() {
+            for name in names.iter().flatten() {
+                if let Ok(repo) = self.repo.find_worktree(name).and_then(|wt| Repository::open_from_worktree(&wt)) {
+                    repos.push(repo);
+                }
+            }
+        }
+        
+        repos.into_iter().find_map(|repo| {
+            let head = repo.find_reference("HEAD").ok()?;
+            if head.symbolic_target() == Some(target.as_str()) {
+                repo.workdir().map(|path| path.to_path_buf())
+            } else {
+                None
+            }
+        })
     }
     
+    // メインworktreeを先頭に、リンクされたworktreeを続けて返す
     pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
-        let worktrees = self.repo.worktrees()?;
+        let current = self.repo.workdir().map(normalize_workdir);
+        let is_current = |path: &Path| current.as_deref().is_some_and(|current| same_path(current, path));
         let mut infos = Vec::new();
         
+        // bareリポジトリにはメインworktreeがない
+        let main_repo = self.open_main_repository()?;
+        if let Some(path) = main_repo.workdir().map(normalize_workdir) {
+            infos.push(WorktreeInfo {
+                name: MAIN_WORKTREE_NAME.to_string(),
+                branch: head_branch(&main_repo),
+                is_prunable: false,
+                is_main: true,
+                is_current: is_current(&path),
+                is_detached: main_repo.head_detached().unwrap_or(false),
+                is_locked: false,
+                lock_reason: None,
+                head: main_repo.head().ok().and_then(|head| head.target()),
+                status: None,
+                path,
+            });
+        }
+        
+        let worktrees = self.repo.worktrees()?;
         for worktree_name in worktrees.iter().flatten() {
             if let Ok(worktree) = self.repo.find_worktree(worktree_name) {
                 let path = worktree.path();
                 let is_prunable = worktree.is_prunable(None).unwrap_or(false);
                 
-                let branch = self.get_worktree_branch(&worktree)?;
-                
-                // ステータス情報を取得（エラーの場合はNone）
-                let status = GitStatus::from_path(&path).ok();
+                // ディレクトリが失われていても一覧には出す（管理情報のHEADから読む）
+                let (branch, is_detached, head) = match Repository::open(path) {
+                    Ok(worktree_repo) => (
+                        head_branch(&worktree_repo),
+                        worktree_repo.head_detached().unwrap_or(false),
+                        worktree_repo.head().ok().and_then(|head| head.target()),
+                    ),
+                    Err(_) => {
+                        let (branch, is_detached) = self.read_admin_head(worktree_name);
+                        (branch, is_detached, None)
+                    }
+                };
+                let lock_reason = match worktree.is_locked()? {
+                    WorktreeLockStatus::Locked(reason) => Some(reason),
+                    WorktreeLockStatus::Unlocked => None,
+                };
                 
                 infos.push(WorktreeInfo {
                     name: worktree_name.to_string(),
                     path: path.to_path_buf(),
                     branch,
                     is_prunable,
-                    status,
+                    is_main: false,
+                    is_current: is_current(path),
+                    is_detached,
+                    is_locked: lock_reason.is_some(),
+                    lock_reason: lock_reason.flatten(),
+                    head,
+                    status: None,
                 });
             }
         }
@@ -133,66 +333,343 @@ impl GitWorktree {
         Ok(infos)
     }
     
+    // オブジェクトはworktree間で共有されるので、どのworktreeのコミットでも引ける
+    pub fn commit_summary(&self, oid: Oid) -> Option<CommitSummary> {
+        let commit = self.repo.find_commit(oid).ok()?;
+        Some(CommitSummary {
+            short_id: short_id(oid),
+            subject: commit.summary().unwrap_or("").to_string(),
+            time: commit.time().seconds(),
+        })
+    }
+    
     pub fn remove_worktree(&self, name: &str, force: bool) -> Result<()> {
-        // 🟢 GREEN: git worktree removeを使用する修正版
-        let worktrees = self.list_worktrees()?;
-        let worktree_info = worktrees
-            .iter()
-            .find(|w| w.name == name)
-            .ok_or_else(|| GitGardenerError::WorktreeNotFound { 
-                name: name.to_string() 
-            })?;
+        if name == MAIN_WORKTREE_NAME {
+            return Err(GitGardenerError::MainWorktree { action: "removed" });
+        }
+        let worktree = self.find_worktree(name)?;
         
-        // gitコマンドを使用してworktreeを削除
-        let path_str = worktree_info.path.to_string_lossy();
-        let mut args = vec!["worktree", "remove"];
-        if force {
-            args.push("--force");
+        if !force {
+            if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+                return Err(GitGardenerError::WorktreeLocked { name: name.to_string(), reason });
+            }
+            
+            // 未コミットの変更（未追跡ファイルやサブモジュール内の変更を含む）があれば削除しない
+            if worktree.path().exists() {
+                let status = GitStatus::for_removal(worktree.path())?;
+                if status.working_tree_status == WorktreeStatus::Dirty {
+                    return Err(GitGardenerError::WorktreeDirty { name: name.to_string() });
+                }
+            }
+        }
+        
+        // 管理情報とワーキングツリーのディレクトリをまとめて削除する
+        let mut options = WorktreePruneOptions::new();
+        options.valid(true).locked(force).working_tree(true);
+        worktree.prune(Some(&mut options))?;
+        
+        Ok(())
+    }
+    
+    // worktreeのディレクトリを移動し、gitの管理情報（gitdir）を新しい場所に向ける
+    pub fn move_worktree(&self, name: &str, new_path: &Path) -> Result<PathBuf> {
+        if name == MAIN_WORKTREE_NAME {
+            return Err(GitGardenerError::MainWorktree { action: "moved" });
+        }
+        let worktree = self.find_worktree(name)?;
+        if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+            return Err(GitGardenerError::WorktreeLocked { name: name.to_string(), reason });
+        }
+        if new_path.exists() {
+            return Err(GitGardenerError::WorktreePathExists {
+                path: new_path.display().to_string(),
+            });
+        }
+        
+        if let Some(parent) = new_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // 別のファイルシステムへはrenameできない（コピーして消すと途中で失敗したときに戻せないので行わない）
+        std::fs::rename(worktree.path(), new_path).map_err(|e| {
+            if is_cross_device(&e) {
+                GitGardenerError::CrossDeviceMove {
+                    from: worktree.path().display().to_string(),
+                    to: new_path.display().to_string(),
+                }
+            } else {
+                e.into()
+            }
+        })?;
+        
+        // gitdirを更新できなければ、gitの管理情報と食い違わないようディレクトリを元の場所に戻す
+        let update_gitdir = || -> Result<PathBuf> {
+            let new_path = new_path.canonicalize()?;
+            let admin_dir = self.get_common_dir().join("worktrees").join(name);
+            std::fs::write(admin_dir.join("gitdir"), format!("{}\n", new_path.join(".git").display()))?;
+            Ok(new_path)
+        };
+        update_gitdir().map_err(|e| {
+            let _ = std::fs::rename(new_path, worktree.path());
+            e
+        })
+    }
+    
+    // `.git/worktrees/<name>`をブランチ名に合わせて改名し、worktree側の`.git`ファイルを更新する
+    pub fn rename_worktree_admin(&self, name: &str, branch_name: &str) -> Result<String> {
+        if sanitize_worktree_name(branch_name) == name {
+            return Ok(name.to_string());
         }
-        args.push(&path_str);
         
-        let output = std::process::Command::new("git")
-            .args(&args)
-            .output()
-            .map_err(|e| GitGardenerError::Custom(format!("Failed to execute git worktree remove: {}", e)))?;
+        let worktree = self.find_worktree(name)?;
+        let new_name = self.unique_worktree_name(branch_name);
+        let worktrees_dir = self.get_common_dir().join("worktrees");
+        std::fs::rename(worktrees_dir.join(name), worktrees_dir.join(&new_name))?;
+        let written = std::fs::write(
+            worktree.path().join(".git"),
+            format!("gitdir: {}\n", worktrees_dir.join(&new_name).display()),
+        );
+        if let Err(e) = written {
+            // worktree側の.gitが古い名前を指したままなので、管理情報の名前も元に戻す
+            let _ = std::fs::rename(worktrees_dir.join(&new_name), worktrees_dir.join(name));
+            return Err(e.into());
+        }
         
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(GitGardenerError::Custom(format!("git worktree remove failed: {}", error_msg)));
+        Ok(new_name)
+    }
+    
+    pub fn rename_branch(&self, old_name: &str, new_name: &str) -> Result<()> {
+        if self.branch_exists(new_name)? {
+            return Err(GitGardenerError::BranchExists { name: new_name.to_string() });
         }
         
+        let mut branch = self.repo
+            .find_branch(old_name, BranchType::Local)
+            .map_err(|_| GitGardenerError::BranchNotFound { name: old_name.to_string() })?;
+        branch.rename(new_name, false)?;
+        Ok(())
+    }
+    
+    pub fn lock_worktree(&self, name: &str, reason: Option<&str>) -> Result<()> {
+        if name == MAIN_WORKTREE_NAME {
+            return Err(GitGardenerError::MainWorktree { action: "locked" });
+        }
+        let worktree = self.find_worktree(name)?;
+        if let WorktreeLockStatus::Locked(reason) = worktree.is_locked()? {
+            return Err(GitGardenerError::WorktreeLocked { name: name.to_string(), reason });
+        }
+        
+        worktree.lock(reason)?;
+        Ok(())
+    }
+    
+    pub fn unlock_worktree(&self, name: &str) -> Result<()> {
+        let worktree = self.find_worktree(name)?;
+        if let WorktreeLockStatus::Unlocked = worktree.is_locked()? {
+            return Err(GitGardenerError::WorktreeNotLocked { name: name.to_string() });
+        }
+        
+        worktree.unlock()?;
+        Ok(())
+    }
+    
+    // ディレクトリが失われたworktreeの管理情報を削除する（有効なworktree、forceなしではロック中のworktreeも対象外）
+    pub fn prune_worktree(&self, name: &str, force: bool) -> Result<()> {
+        let mut options = WorktreePruneOptions::new();
+        options.locked(force);
+        self.find_worktree(name)?.prune(Some(&mut options))?;
         Ok(())
     }
     
+    // worktreeをpruneできる理由（有効なworktreeならNone）
+    pub fn prune_reason(&self, name: &str) -> Result<Option<String>> {
+        let worktree = self.find_worktree(name)?;
+        if worktree.validate().is_ok() {
+            return Ok(None);
+        }
+        
+        let reason = if !worktree.path().exists() {
+            "directory missing"
+        } else {
+            "gitdir is broken"
+        };
+        Ok(Some(reason.to_string()))
+    }
+    
+    // `.git/worktrees/<name>/HEAD`から（ブランチ名, detachedかどうか）を読む
+    fn read_admin_head(&self, name: &str) -> (String, bool) {
+        let head_path = self.get_common_dir().join("worktrees").join(name).join("HEAD");
+        let Ok(contents) = std::fs::read_to_string(head_path) else {
+            return ("(unknown)".to_string(), false);
+        };
+        
+        let contents = contents.trim();
+        match contents.strip_prefix("ref: ") {
+            Some(reference) => (reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string(), false),
+            None => match Oid::from_str(contents) {
+                Ok(oid) => (format!("(detached at {})", short_id(oid)), true),
+                Err(_) => ("(unknown)".to_string(), false),
+            },
+        }
+    }
+    
+    fn find_worktree(&self, name: &str) -> Result<git2::Worktree> {
+        self.repo
+            .find_worktree(name)
+            .map_err(|_| GitGardenerError::WorktreeNotFound { name: name.to_string() })
+    }
+    
+    // メインworktreeのパス（リンクされたworktree内から実行しても共通のgitディレクトリから解決する）
     pub fn get_repository_root(&self) -> Result<PathBuf> {
-        Ok(self.repo.workdir()
+        let main_repo = self.open_main_repository()?;
+        main_repo.workdir()
+            .map(normalize_workdir)
             .ok_or_else(|| GitGardenerError::Custom(
                 "Could not determine repository root".to_string()
-            ))?
-            .to_path_buf())
+            ))
+    }
+    
+    fn open_main_repository(&self) -> Result<Repository> {
+        Ok(Repository::open(self.get_common_dir())?)
+    }
+    
+    // コマンドを実行したworktree（カレントディレクトリを含むworktree）のパスとブランチ
+    pub fn get_current_worktree(&self) -> Result<(PathBuf, String)> {
+        let path = self.repo.workdir()
+            .map(normalize_workdir)
+            .ok_or_else(|| GitGardenerError::Custom(
+                "Could not determine current worktree".to_string()
+            ))?;
+        
+        Ok((path, head_branch(&self.repo)))
     }
     
-    fn get_worktree_branch(&self, worktree: &git2::Worktree) -> Result<String> {
-        let worktree_repo = Repository::open(worktree.path())?;
+    // git-gardenerの状態を保存するディレクトリ（全worktreeで共有される）
+    pub fn get_state_dir(&self) -> PathBuf {
+        self.get_common_dir().join("gardener")
+    }
+    
+    // 全worktreeで共有される.gitディレクトリ（リンクされたworktreeでは`commondir`ファイルが指す先）
+    pub fn get_common_dir(&self) -> PathBuf {
+        common_dir(&self.repo)
+    }
+    
+    pub fn delete_branch(&self, branch_name: &str) -> Result<()> {
+        let mut branch = self.repo.find_branch(branch_name, BranchType::Local)?;
+        branch.delete()?;
+        Ok(())
+    }
+    
+    pub fn remote_names(&self) -> Result<Vec<String>> {
+        Ok(self.repo.remotes()?.iter().flatten().map(|name| name.to_string()).collect())
+    }
+    
+    // リモートの既定のrefspecでfetchする
+    pub fn fetch_remote(&self, remote_name: &str) -> Result<()> {
+        let mut remote = self.repo
+            .find_remote(remote_name)
+            .map_err(|_| GitGardenerError::RemoteNotFound { name: remote_name.to_string() })?;
         
-        if let Ok(head) = worktree_repo.head() {
-            if let Some(name) = head.shorthand() {
-                return Ok(name.to_string());
+        // SSHはエージェント、HTTPSはgitのcredential helperで認証する
+        let config = self.repo.config()?;
+        let mut attempts = 0;
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |url, username, allowed| {
+            attempts += 1;
+            if attempts > 3 {
+                return Err(git2::Error::from_str("authentication failed"));
             }
+            if allowed.contains(CredentialType::SSH_KEY) {
+                if let Some(username) = username {
+                    return Cred::ssh_key_from_agent(username);
+                }
+            }
+            if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+                return Cred::credential_helper(&config, url, username);
+            }
+            Cred::default()
+        });
+        
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        remote.fetch(&[] as &[&str], Some(&mut options), None)?;
+        Ok(())
+    }
+    
+    // ローカルにないブランチをリモート追跡ブランチから探す
+    // （`origin/feature-x`の形式、またはいずれかのリモートにある`feature-x`）
+    pub fn find_remote_branch(&self, name: &str, remote: Option<&str>) -> Result<Option<RemoteBranch>> {
+        let remotes = self.remote_names()?;
+        if let Some(remote) = remote {
+            if !remotes.iter().any(|r| r == remote) {
+                return Err(GitGardenerError::RemoteNotFound { name: remote.to_string() });
+            }
+        }
+        
+        let exists = |branch: &RemoteBranch| {
+            self.repo
+                .find_branch(&branch.shorthand(), BranchType::Remote)
+                .is_ok()
+        };
+        let selected = |candidate: &String| remote.map_or(true, |remote| candidate == remote);
+        
+        // リモート名付きで指定された場合はそれを優先する
+        let prefixed: Vec<RemoteBranch> = remotes
+            .iter()
+            .filter(|candidate| selected(candidate))
+            .filter_map(|candidate| {
+                name.strip_prefix(candidate.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .map(|rest| RemoteBranch { remote: candidate.clone(), name: rest.to_string() })
+            })
+            .filter(|branch| exists(branch))
+            .collect();
+        if let Some(branch) = prefixed.into_iter().next() {
+            return Ok(Some(branch));
+        }
+        
+        let mut matches: Vec<RemoteBranch> = remotes
+            .iter()
+            .filter(|candidate| selected(candidate))
+            .map(|candidate| RemoteBranch { remote: candidate.clone(), name: name.to_string() })
+            .filter(|branch| exists(branch))
+            .collect();
+        
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(matches.pop()),
+            _ => Err(GitGardenerError::AmbiguousRemoteBranch {
+                name: name.to_string(),
+                remotes: matches.iter().map(|branch| branch.remote.as_str()).collect::<Vec<_>>().join(", "),
+            }),
+        }
+    }
+    
+    // リモートブランチを追跡するローカルブランチを作成する
+    pub fn create_tracking_branch(&self, local_name: &str, remote_branch: &RemoteBranch) -> Result<()> {
+        if self.branch_exists(local_name)? {
+            return Err(GitGardenerError::BranchExists { name: local_name.to_string() });
         }
         
-        Ok("(unknown)".to_string())
+        let commit = self.repo
+            .find_branch(&remote_branch.shorthand(), BranchType::Remote)?
+            .get()
+            .peel_to_commit()?;
+        let mut branch = self.repo.branch(local_name, &commit, false)?;
+        if let Err(e) = branch.set_upstream(Some(&remote_branch.shorthand())) {
+            let _ = branch.delete();
+            return Err(e.into());
+        }
+        
+        Ok(())
     }
     
     pub fn branch_exists(&self, branch_name: &str) -> Result<bool> {
         let branches = self.repo.branches(Some(BranchType::Local))?;
         
-        for branch_result in branches {
-            if let Ok((branch, _)) = branch_result {
-                if let Some(name) = branch.name()? {
-                    if name == branch_name {
-                        return Ok(true);
-                    }
+        for (branch, _) in branches.flatten() {
+            if let Some(name) = branch.name()? {
+                if name == branch_name {
+                    return Ok(true);
                 }
             }
         }
@@ -230,81 +707,493 @@ impl GitWorktree {
             }
         };
         
-        // ブランチのコミットがベースブランチから到達可能かチェック
-        let is_ancestor = self.repo.graph_descendant_of(base_commit.id(), branch_commit.id())?;
+        // ブランチのコミットがベースブランチから到達可能かチェック（fast-forwardで同じコミットになった場合も含む）
+        let is_ancestor = branch_commit.id() == base_commit.id()
+            || self.repo.graph_descendant_of(base_commit.id(), branch_commit.id())?;
         
         Ok(is_ancestor)
     }
     
-    // 🟢 GREEN: 古いworktreeかどうかの判定（実装）
-    pub fn is_worktree_stale(&self, branch_name: &str, days: u32) -> Result<bool> {
-        // ブランチの参照を取得
-        let branch_ref = format!("refs/heads/{}", branch_name);
+    // 通常のマージに加え、rebaseマージやsquashマージも検出してどの方法で取り込まれたかを返す
+    pub fn branch_merge_method(&self, branch_name: &str, base_branch: &str) -> Result<Option<MergeMethod>> {
+        if self.is_branch_merged(branch_name, base_branch)? {
+            return Ok(Some(MergeMethod::Ancestor));
+        }
         
-        let branch_commit = match self.repo.find_reference(&branch_ref) {
-            Ok(reference) => {
-                let oid = reference.target().ok_or_else(|| {
-                    GitGardenerError::Custom(format!("Branch {} has no target", branch_name))
-                })?;
-                self.repo.find_commit(oid)?
-            }
-            Err(_) => {
-                // ブランチが存在しない場合はfalse（削除できない）
-                return Ok(false);
-            }
+        let (Some(branch_oid), Some(base_oid)) = (
+            self.branch_target(branch_name)?,
+            self.branch_target(base_branch)?,
+        ) else {
+            return Ok(None);
         };
-        
-        // 最後のコミット日時を取得
-        let commit_time = branch_commit.time().seconds();
-        
-        // 現在時刻から指定日数前の時刻を計算
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-        let threshold_time = now - (days as i64 * 24 * 60 * 60);
-        
-        // コミット時刻が閾値より古いかどうかを判定
-        Ok(commit_time < threshold_time)
-    }
-    
-    // 🔴 RED: ステータス情報付きワーキングツリー一覧取得（まだ基本実装のみ）
-    pub fn list_worktrees_with_status(&self) -> Result<Vec<WorktreeInfoWithStatus>> {
-        let worktrees = self.list_worktrees()?;
-        let mut result = Vec::new();
-        
-        for worktree in worktrees {
-            // 各ワーキングツリーのステータスを取得
-            let status = GitStatus::from_path(&worktree.path).unwrap_or_else(|_| {
-                // エラーの場合はデフォルトステータスを使用
-                GitStatus {
-                    working_tree_status: WorktreeStatus::Clean,
-                    has_staged_changes: false,
-                    has_unstaged_changes: false,
-                    last_commit_time: None,
-                    ahead_count: 0,
-                    behind_count: 0,
+        let merge_base = match self.repo.merge_base(branch_oid, base_oid) {
+            Ok(oid) => oid,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        // ブランチのすべてのコミットと同じ変更（patch-id）がベースにあればrebaseマージ
+        // ベース側はマージベース以降のコミットだけを見て、すべて見つかった時点で打ち切る
+        let mut missing = self.patch_ids(branch_oid, merge_base)?;
+        if !missing.is_empty() {
+            let mut revwalk = self.repo.revwalk()?;
+            revwalk.push(base_oid)?;
+            revwalk.hide(merge_base)?;
+            for oid in revwalk {
+                if let Some(id) = self.patch_id(oid?)? {
+                    missing.remove(&id);
+                    if missing.is_empty() {
+                        return Ok(Some(MergeMethod::Rebase));
+                    }
                 }
-            });
-            
-            result.push(WorktreeInfoWithStatus {
-                name: worktree.name,
-                path: worktree.path,
-                branch: worktree.branch,
-                is_prunable: worktree.is_prunable,
-                status,
-            });
+            }
         }
         
-        Ok(result)
+        // ブランチの変更をまとめてベースにマージしても何も変わらなければsquashマージ
+        let base_tree = self.repo.find_commit(base_oid)?.tree()?;
+        let mut index = self.repo.merge_trees(
+            &self.repo.find_commit(merge_base)?.tree()?,
+            &base_tree,
+            &self.repo.find_commit(branch_oid)?.tree()?,
+            None,
+        )?;
+        if !index.has_conflicts() && index.write_tree_to(&self.repo)? == base_tree.id() {
+            return Ok(Some(MergeMethod::Squash));
+        }
+        
+        Ok(None)
+    }
+    
+    fn branch_target(&self, branch_name: &str) -> Result<Option<Oid>> {
+        match self.repo.find_branch(branch_name, BranchType::Local) {
+            Ok(branch) => Ok(branch.get().target()),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    // fromから到達でき、hideからは到達できない非マージコミットのpatch-id
+    fn patch_ids(&self, from: Oid, hide: Oid) -> Result<HashSet<Oid>> {
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(from)?;
+        revwalk.hide(hide)?;
+        
+        let mut ids = HashSet::new();
+        for oid in revwalk {
+            ids.extend(self.patch_id(oid?)?);
+        }
+        Ok(ids)
+    }
+    
+    // コミットの変更のpatch-id（マージコミットはNone）
+    fn patch_id(&self, oid: Oid) -> Result<Option<Oid>> {
+        if let Some(id) = self.patch_ids.borrow().get(&oid) {
+            return Ok(*id);
+        }
+        
+        let commit = self.repo.find_commit(oid)?;
+        let id = match commit.parent_count() {
+            0 | 1 => {
+                let parent_tree = match commit.parent_count() {
+                    0 => None,
+                    _ => Some(commit.parent(0)?.tree()?),
+                };
+                let diff = self.repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+                Some(diff.patchid(None)?)
+            }
+            _ => None,
+        };
+        self.patch_ids.borrow_mut().insert(oid, id);
+        Ok(id)
+    }
+    
+    // 🟢 GREEN: 古いworktreeかどうかの判定（実装）
+    // 指定日数以上作業されていないworktreeか（コマンドを実行中のworktreeは対象外）
+    // 古いコミットをチェックアウトしたばかりのworktreeもあるので、ブランチ先端のコミット日時ではなくworktreeの活動で判定する
+    pub fn is_worktree_stale(&self, worktree: &WorktreeInfo, days: u32) -> bool {
+        if worktree.is_current {
+            return false;
+        }
+        let Some(last_activity) = self.last_activity(worktree) else {
+            return false;
+        };
+        let threshold = std::time::Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+        last_activity.elapsed().is_ok_and(|age| age > threshold)
+    }
+    
+    // worktreeで最後に作業した時刻（管理ディレクトリのHEAD・index・HEADのreflogのうち最も新しい更新時刻）
+    fn last_activity(&self, worktree: &WorktreeInfo) -> Option<std::time::SystemTime> {
+        let common_dir = self.get_common_dir();
+        let admin_dir = if worktree.is_main {
+            common_dir
+        } else {
+            common_dir.join("worktrees").join(&worktree.name)
+        };
+        ["HEAD", "index", "logs/HEAD"]
+            .iter()
+            .filter_map(|file| std::fs::metadata(admin_dir.join(file)).and_then(|metadata| metadata.modified()).ok())
+            .max()
+    }
+}
+
+// リンクされたworktreeでもメインのgitディレクトリ（refsやlogsを共有する場所）を返す
+pub(crate) fn common_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    if repo.is_worktree() {
+        if let Ok(contents) = std::fs::read_to_string(git_dir.join("commondir")) {
+            let common_dir = git_dir.join(contents.trim());
+            return common_dir.canonicalize().unwrap_or(common_dir);
+        }
+    }
+    
+    git_dir.to_path_buf()
+}
+
+// HEADのブランチ名（detached HEADの場合は"(detached at <sha>)"）
+fn head_branch(repo: &Repository) -> String {
+    let Ok(head) = repo.head() else {
+        return "(unknown)".to_string();
+    };
+    
+    if repo.head_detached().unwrap_or(false) {
+        if let Some(oid) = head.target() {
+            return format!("(detached at {})", short_id(oid));
+        }
+    }
+    
+    head.shorthand()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| "(unknown)".to_string())
+}
+
+fn sanitize_worktree_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c == '/' || c == '\\' { '-' } else { c })
+        .collect()
+}
+
+// 一覧のworktreeのステータスを並列に取得して埋める（時間内に取得できなかったものはNoneのまま）
+// cacheを渡すと、変更のないworktreeはキャッシュから読む
+pub fn load_statuses(
+    worktrees: &mut [WorktreeInfo],
+    query: &StatusQuery,
+    timeout: std::time::Duration,
+    cache: Option<&mut StatusCache>,
+) {
+    // ディレクトリのないworktreeは調べない
+    let paths: Vec<PathBuf> = worktrees
+        .iter()
+        .filter(|w| !w.is_prunable)
+        .map(|w| w.path.clone())
+        .collect();
+    let statuses = match cache {
+        Some(cache) => cache.collect(&paths, query, timeout),
+        None => GitStatus::collect(&paths, query, timeout),
+    };
+    let mut statuses = statuses.into_iter();
+    for worktree in worktrees.iter_mut().filter(|w| !w.is_prunable) {
+        worktree.status = statuses.next().flatten();
+    }
+}
+
+// 空になった親ディレクトリをrootの手前まで削除する
+pub fn remove_empty_parents(path: &Path, root: &Path) {
+    let mut current = path.parent();
+    while let Some(dir) = current {
+        if dir == root || !dir.starts_with(root) || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+// renameが別のファイルシステムへの移動で失敗したか
+fn is_cross_device(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        e.raw_os_error() == Some(libc::EXDEV)
+    }
+    #[cfg(not(unix))]
+    {
+        // ERROR_NOT_SAME_DEVICE
+        e.raw_os_error() == Some(17)
+    }
+}
+
+fn worktree_add_error(e: git2::Error, path: &Path) -> GitGardenerError {
+    match e.code() {
+        ErrorCode::Exists => GitGardenerError::WorktreePathExists {
+            path: path.display().to_string(),
+        },
+        _ => GitGardenerError::Git(e),
+    }
+}
+
+fn short_id(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+// libgit2のworkdirは末尾に'/'が付くので取り除く
+fn normalize_workdir(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+// 存在する最長の祖先を実体のパスに解決し、まだ存在しない残りの要素を付け直す
+pub fn canonicalize_existing(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest.iter().rev().fold(canonical, |acc, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{commit_all, git, setup_git_repo};
+    use std::fs;
+    use std::process::Command;
     use tempfile::tempdir;
     
+    #[test]
+    fn test_create_worktree_with_new_branch() {
+        // What: libgit2でブランチとworktreeが作成され、'/'を含む名前が置き換えられるかテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let path = temp_dir.path().join(".gardener/feature/x");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        
+        git_worktree.create_worktree_with_commit("feature/x", &path, "feature/x", true, None).unwrap();
+        
+        assert!(path.join("README.md").exists());
+        let worktrees = git_worktree.list_worktrees().unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert_eq!(worktrees[1].name, "feature-x");
+        assert_eq!(worktrees[1].branch, "feature/x");
+    }
+    
+    #[test]
+    fn test_create_worktree_errors_are_typed() {
+        // What: 作成時のエラーが型付きのエラーとして返されるかテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let path = temp_dir.path().join("wt");
+        
+        let missing = git_worktree.create_worktree_with_commit("wt", &path, "missing", false, None);
+        assert!(matches!(missing.unwrap_err(), GitGardenerError::BranchNotFound { .. }));
+        
+        let head = git_worktree.repo.head().unwrap().shorthand().unwrap().to_string();
+        let checked_out = git_worktree.create_worktree_with_commit("wt", &path, &head, false, None);
+        assert!(matches!(checked_out.unwrap_err(), GitGardenerError::BranchCheckedOut { .. }));
+        
+        let bad_rev = git_worktree.create_worktree_with_commit("wt", &path, "new", true, Some("no-such-rev"));
+        assert!(matches!(bad_rev.unwrap_err(), GitGardenerError::InvalidRevision { .. }));
+        assert!(!git_worktree.branch_exists("new").unwrap());
+        
+        fs::create_dir_all(&path).unwrap();
+        let exists = git_worktree.create_worktree_with_commit("wt", &path, "new", true, None);
+        assert!(matches!(exists.unwrap_err(), GitGardenerError::WorktreePathExists { .. }));
+    }
+    
+    #[test]
+    fn test_create_detached_worktree() {
+        // What: リビジョンの解決と、detached HEADのworktree作成（一時ブランチが残らないこと）をテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        
+        let (oid, name) = git_worktree.resolve_detach_target("HEAD~0").unwrap();
+        assert_eq!(name, format!("detached-{}", short_id(oid)));
+        assert!(matches!(
+            git_worktree.resolve_detach_target("HEAD~5").unwrap_err(),
+            GitGardenerError::InvalidRevision { .. }
+        ));
+        
+        let path = temp_dir.path().join(&name);
+        git_worktree.create_detached_worktree(&name, &path, oid).unwrap();
+        
+        let worktree_repo = Repository::open(&path).unwrap();
+        assert!(worktree_repo.head_detached().unwrap());
+        assert_eq!(worktree_repo.head().unwrap().target(), Some(oid));
+        assert_eq!(git_worktree.repo.branches(Some(BranchType::Local)).unwrap().count(), 1);
+        
+        let worktrees = git_worktree.list_worktrees().unwrap();
+        assert!(worktrees[1].is_detached);
+        assert_eq!(worktrees[1].branch, format!("(detached at {})", short_id(oid)));
+    }
+    
+    #[test]
+    fn test_create_detached_worktree_keeps_existing_temp_branch_name() {
+        // What: 一時ブランチと同じ名前のブランチが既にあっても上書き・削除されないかテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let (oid, name) = git_worktree.resolve_detach_target("HEAD").unwrap();
+        fs::write(temp_dir.path().join("next.txt"), "next").unwrap();
+        commit_all(temp_dir.path(), "Next commit");
+        git(temp_dir.path(), &["branch", &format!("gardener-detach/{}", name)]);
+        let existing = git_worktree.repo.find_branch(&format!("gardener-detach/{}", name), BranchType::Local)
+            .unwrap().get().target();
+        
+        let path = temp_dir.path().join(&name);
+        git_worktree.create_detached_worktree(&name, &path, oid).unwrap();
+        
+        let branch = git_worktree.repo.find_branch(&format!("gardener-detach/{}", name), BranchType::Local).unwrap();
+        assert_eq!(branch.get().target(), existing);
+        assert_eq!(git_worktree.repo.branches(Some(BranchType::Local)).unwrap().count(), 2);
+    }
+    
+    #[test]
+    fn test_find_remote_branch_with_remote_prefix() {
+        // What: `origin/feature-x`の形式でリモートブランチが見つかり、追跡ブランチを作成できるかテスト
+        let temp_dir = setup_git_repo();
+        let remote_dir = tempdir().unwrap();
+        let bare_path = remote_dir.path().join("origin.git");
+        Command::new("git").args(["init", "--bare", &bare_path.to_string_lossy()]).output().unwrap();
+        for args in [
+            vec!["remote", "add", "origin", &bare_path.to_string_lossy()],
+            vec!["push", "origin", "HEAD:refs/heads/feature-x"],
+            // pushで作られた追跡ブランチを消し、fetchするまで見えない状態にする
+            vec!["update-ref", "-d", "refs/remotes/origin/feature-x"],
+        ] {
+            git(temp_dir.path(), &args);
+        }
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        
+        assert!(git_worktree.find_remote_branch("feature-x", None).unwrap().is_none());
+        git_worktree.fetch_remote("origin").unwrap();
+        
+        let remote_branch = git_worktree.find_remote_branch("origin/feature-x", None).unwrap().unwrap();
+        assert_eq!(remote_branch, RemoteBranch { remote: "origin".to_string(), name: "feature-x".to_string() });
+        assert!(matches!(
+            git_worktree.find_remote_branch("feature-x", Some("missing")).unwrap_err(),
+            GitGardenerError::RemoteNotFound { .. }
+        ));
+        
+        git_worktree.create_tracking_branch("feature-x", &remote_branch).unwrap();
+        let branch = git_worktree.repo.find_branch("feature-x", BranchType::Local).unwrap();
+        assert_eq!(branch.upstream().unwrap().name().unwrap(), Some("origin/feature-x"));
+    }
+    
+    #[test]
+    fn test_remove_worktree_refuses_dirty_submodule() {
+        // What: サブモジュール内に未コミットの変更があるworktreeはforceなしで削除されないかテスト
+        let library = setup_git_repo();
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        let library_path = library.path().to_string_lossy().to_string();
+        git(repo_path, &["-c", "protocol.file.allow=always", "submodule", "add", "-q", &library_path, "lib"]);
+        git(repo_path, &["commit", "-q", "-m", "Add submodule"]);
+        let git_worktree = GitWorktree::from_path(repo_path).unwrap();
+        let path = repo_path.join("wt");
+        git_worktree.create_worktree("wt", &path, "wt", true).unwrap();
+        git(&path, &["-c", "protocol.file.allow=always", "submodule", "update", "-q", "--init"]);
+        
+        fs::write(path.join("lib/README.md"), "local edit").unwrap();
+        let dirty = git_worktree.remove_worktree("wt", false);
+        assert!(matches!(dirty.unwrap_err(), GitGardenerError::WorktreeDirty { .. }));
+        
+        git(&path.join("lib"), &["checkout", "--", "README.md"]);
+        git_worktree.remove_worktree("wt", false).unwrap();
+        assert!(!path.exists());
+    }
+    
+    #[test]
+    fn test_remove_worktree_refuses_dirty_and_locked() {
+        // What: 変更のある・ロックされたworktreeはforceなしで削除されないかテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let path = temp_dir.path().join("wt");
+        git_worktree.create_worktree("wt", &path, "wt", true).unwrap();
+        
+        fs::write(path.join("untracked.txt"), "data").unwrap();
+        let dirty = git_worktree.remove_worktree("wt", false);
+        assert!(matches!(dirty.unwrap_err(), GitGardenerError::WorktreeDirty { .. }));
+        fs::remove_file(path.join("untracked.txt")).unwrap();
+        
+        git_worktree.lock_worktree("wt", Some("on usb drive")).unwrap();
+        let locked = git_worktree.remove_worktree("wt", false);
+        assert!(matches!(
+            locked.unwrap_err(),
+            GitGardenerError::WorktreeLocked { reason: Some(reason), .. } if reason == "on usb drive"
+        ));
+        
+        git_worktree.unlock_worktree("wt").unwrap();
+        git_worktree.remove_worktree("wt", false).unwrap();
+        
+        assert!(!path.exists());
+        assert!(git_worktree.list_worktrees().unwrap().iter().all(|w| w.is_main));
+        
+        let main = git_worktree.remove_worktree(MAIN_WORKTREE_NAME, true);
+        assert!(matches!(main.unwrap_err(), GitGardenerError::MainWorktree { .. }));
+    }
+    
+    #[test]
+    fn test_prune_worktree_removes_missing_worktree() {
+        // What: ディレクトリが削除されたworktreeの管理情報をpruneできるかテスト
+        let temp_dir = setup_git_repo();
+        let git_worktree = GitWorktree::from_path(temp_dir.path()).unwrap();
+        let path = temp_dir.path().join("wt");
+        git_worktree.create_worktree("wt", &path, "wt", true).unwrap();
+        
+        fs::remove_dir_all(&path).unwrap();
+        let listed = git_worktree.list_worktrees().unwrap();
+        assert!(listed[1].is_prunable);
+        assert_eq!(listed[1].branch, "wt");
+        assert_eq!(git_worktree.prune_reason("wt").unwrap().as_deref(), Some("directory missing"));
+        git_worktree.prune_worktree("wt", false).unwrap();
+        
+        assert!(git_worktree.repo.worktrees().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_branch_merge_method_detects_rebase_and_squash() {
+        // What: rebaseマージ・squashマージされたブランチと、その方法を検出できるかテスト
+        let temp_dir = setup_git_repo();
+        let repo_path = temp_dir.path();
+        git(repo_path, &["branch", "-M", "main"]);
+        git(repo_path, &["branch", "fresh"]);
+        for branch in ["rebased", "squashed", "open"] {
+            git(repo_path, &["checkout", "-q", "-b", branch, "main"]);
+            for n in 1..=2 {
+                fs::write(repo_path.join(format!("{}-{}.txt", branch, n)), "change").unwrap();
+                git(repo_path, &["add", "."]);
+                git(repo_path, &["commit", "-q", "-m", &format!("{} {}", branch, n)]);
+            }
+        }
+        // mainを先に進めてから取り込む（フォージでのrebaseマージ・squashマージ相当）
+        git(repo_path, &["checkout", "-q", "main"]);
+        fs::write(repo_path.join("main.txt"), "main").unwrap();
+        git(repo_path, &["add", "."]);
+        git(repo_path, &["commit", "-q", "-m", "main work"]);
+        git(repo_path, &["cherry-pick", "main..rebased"]);
+        git(repo_path, &["merge", "--squash", "squashed"]);
+        git(repo_path, &["commit", "-q", "-m", "Squashed"]);
+        let git_worktree = GitWorktree::from_path(repo_path).unwrap();
+        
+        assert_eq!(git_worktree.branch_merge_method("rebased", "main").unwrap(), Some(MergeMethod::Rebase));
+        assert_eq!(git_worktree.branch_merge_method("squashed", "main").unwrap(), Some(MergeMethod::Squash));
+        assert_eq!(git_worktree.branch_merge_method("open", "main").unwrap(), None);
+        assert_eq!(git_worktree.branch_merge_method("fresh", "main").unwrap(), Some(MergeMethod::Ancestor));
+        // mainと同じコミットのブランチ（fast-forwardでマージされた、または分岐していない）
+        git(repo_path, &["branch", "fast-forwarded", "main"]);
+        assert_eq!(git_worktree.branch_merge_method("fast-forwarded", "main").unwrap(), Some(MergeMethod::Ancestor));
+        assert!(!git_worktree.is_branch_merged("squashed", "main").unwrap());
+    }
+    
     #[test]
     fn test_not_in_repository() {
         let temp_dir = tempdir().unwrap();
@@ -1,31 +1,157 @@
+use crate::cache::{CacheOutcome, HookCache};
 use crate::config::Hook;
 use crate::error::{GitGardenerError, Result};
-use std::path::Path;
-use std::process::Command;
+use crate::git::{canonicalize_existing, GitWorktree};
+use crate::output;
+use crate::state::{self, HookHistory, HookRun, HookRunStatus};
+use crate::signal::{self, SignalGuard};
+use crate::time;
+use colored::*;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Output, Stdio};
 use std::collections::HashMap;
+use std::time::Instant;
 
-pub struct HookExecutor;
+pub struct HookExecutor {
+    cache: Option<HookCache>,
+    state_dir: Option<PathBuf>,
+    // 出力をその場で表示し、標準入力を渡す（runのタスク向け）
+    live_output: bool,
+}
+
+impl Default for HookExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl HookExecutor {
     pub fn new() -> Self {
-        Self
+        Self {
+            cache: None,
+            state_dir: None,
+            live_output: false,
+        }
+    }
+    
+    // inputs/outputsを宣言したフックのスキップ・キャッシュと実行履歴の記録を有効にする
+    pub fn with_state_dir(state_dir: &Path) -> Self {
+        Self {
+            cache: Some(HookCache::new(state_dir)),
+            state_dir: Some(state_dir.to_path_buf()),
+            live_output: false,
+        }
+    }
+    
+    // コマンドの出力を終了を待たずに表示し、端末の入力を読めるようにする（ログには引き続き残す）
+    pub fn with_live_output(mut self) -> Self {
+        self.live_output = true;
+        self
+    }
+    
+    pub fn execute_hooks(&self, worktree_path: &Path, branch: &str, hooks: &[Hook]) -> Result<Vec<HookRun>> {
+        let mut runs = Vec::new();
+        self.run_hooks(worktree_path, branch, hooks, &mut runs)?;
+        Ok(runs)
+    }
+    
+    // フックを実行して所要時間の一覧を表示する（失敗・中断した場合も、原因の手がかりになるよう表示してから返す）
+    pub fn execute_hooks_with_summary(&self, worktree_path: &Path, branch: &str, hooks: &[Hook]) -> Result<Vec<HookRun>> {
+        let mut runs = Vec::new();
+        let result = self.run_hooks(worktree_path, branch, hooks, &mut runs);
+        Self::print_summary(&runs);
+        result.map(|_| runs)
     }
     
-    pub fn execute_hooks(&self, worktree_path: &Path, branch: &str, hooks: &[Hook]) -> Result<()> {
+    fn run_hooks(&self, worktree_path: &Path, branch: &str, hooks: &[Hook], runs: &mut Vec<HookRun>) -> Result<()> {
+        // ${REPO_ROOT}はフックごとに変わらないので、ここで一度だけ求める
+        let repo_root = Self::repo_root(worktree_path);
+        
+        // 実行前に全フックを検証し、途中まで実行されて失敗する状態を避ける
         for hook in hooks {
-            match &hook.hook_type {
+            self.validate_hook(hook, worktree_path, branch, &repo_root)?;
+        }
+        
+        // 実行中のCtrl-Cはフックのプロセスグループに転送し、呼び出し元にInterruptedとして返す
+        let _signal_guard = SignalGuard::install();
+        
+        for (index, hook) in hooks.iter().enumerate() {
+            if let Some(signal) = signal::received() {
+                return Err(GitGardenerError::Interrupted { signal });
+            }
+            
+            let started_at = time::now_unix();
+            let timer = Instant::now();
+            let log_path = self.log_path(branch, started_at, index);
+            
+            let result = match &hook.hook_type {
                 crate::config::HookType::Copy => {
-                    self.execute_copy_hook(hook, worktree_path)?;
+                    self.execute_copy_hook(hook, worktree_path).map(|_| HookRunStatus::Success)
                 }
                 crate::config::HookType::Command => {
-                    self.execute_command_hook(hook, worktree_path, branch)?;
+                    self.execute_command_hook(hook, worktree_path, branch, &repo_root, log_path.as_deref())
+                }
+            };
+            
+            let (status, exit_code) = match &result {
+                Ok(status) => (*status, None),
+                Err(GitGardenerError::HookFailed { exit_code, .. }) => (HookRunStatus::Failed, *exit_code),
+                Err(GitGardenerError::Interrupted { .. }) => (HookRunStatus::Interrupted, None),
+                Err(_) => (HookRunStatus::Failed, None),
+            };
+            let run = HookRun {
+                worktree: branch.to_string(),
+                worktree_path: worktree_path.to_path_buf(),
+                hook: self.describe_hook(hook, worktree_path, branch, &repo_root),
+                started_at,
+                duration_ms: timer.elapsed().as_millis() as u64,
+                status,
+                exit_code,
+                log_path: log_path.filter(|path| path.exists()),
+            };
+            
+            // 履歴を書けなくてもフックの結果は変えない
+            if let Some(state_dir) = &self.state_dir {
+                if let Err(e) = HookHistory::append(state_dir, run.clone()) {
+                    eprintln!("Warning: Failed to record hook history: {}", e);
                 }
             }
+            runs.push(run);
+            result?;
         }
         
         Ok(())
     }
     
+    // フック実行結果の一覧を表示し、時間のかかったフックを強調する
+    pub fn print_summary(runs: &[HookRun]) {
+        if runs.is_empty() {
+            return;
+        }
+        
+        let total_ms: u64 = runs.iter().map(|run| run.duration_ms).sum();
+        let mut by_duration: Vec<u64> = runs.iter().map(|run| run.duration_ms).collect();
+        by_duration.sort_unstable_by(|a, b| b.cmp(a));
+        let slowest = by_duration[0];
+        // 3件以上ある場合は上位3件（最も遅いもの以外）を黄色にする
+        let third_slowest = by_duration.get(2).copied().unwrap_or(slowest);
+        
+        output::progress("");
+        output::progress(format!("Hook summary ({} total):", time::format_duration(total_ms)));
+        for run in runs {
+            let duration = format!("{:>8}", time::format_duration(run.duration_ms));
+            let duration = if runs.len() > 1 && run.duration_ms == slowest {
+                duration.red().bold()
+            } else if runs.len() > 2 && run.duration_ms >= third_slowest {
+                duration.yellow()
+            } else {
+                duration.normal()
+            };
+            output::progress(format!("  {}  {:<9} {}", duration, run.status.to_string(), run.hook));
+        }
+    }
+    
     fn execute_copy_hook(&self, hook: &Hook, worktree_path: &Path) -> Result<()> {
         let from = hook.from.as_ref()
             .ok_or_else(|| GitGardenerError::Custom("Copy hook requires 'from' field".to_string()))?;
@@ -47,78 +173,392 @@ impl HookExecutor {
         }
         
         std::fs::copy(source, &dest)?;
-        println!("✓ Copied {} to {}", source.display(), dest.display());
+        output::progress(format!("✓ Copied {} to {}", source.display(), dest.display()));
         
         Ok(())
     }
     
-    fn execute_command_hook(&self, hook: &Hook, worktree_path: &Path, branch: &str) -> Result<()> {
+    fn execute_command_hook(
+        &self,
+        hook: &Hook,
+        worktree_path: &Path,
+        branch: &str,
+        repo_root: &Path,
+        log_path: Option<&Path>,
+    ) -> Result<HookRunStatus> {
         let command = hook.command.as_ref()
             .ok_or_else(|| GitGardenerError::Custom("Command hook requires 'command' field".to_string()))?;
         
-        let expanded_command = self.expand_variables(command, worktree_path, branch);
+        let expanded_command = self.expand_variables(command, worktree_path, branch, repo_root);
         
+        let working_dir = match &hook.cwd {
+            Some(cwd) => {
+                let dir = self.resolve_worktree_path(cwd, worktree_path, branch, repo_root, "cwd")?;
+                if !dir.is_dir() {
+                    return Err(GitGardenerError::Custom(
+                        format!("Hook working directory does not exist: {}", dir.display())
+                    ));
+                }
+                dir
+            }
+            None => worktree_path.to_path_buf(),
+        };
+        
+        // env_fileを先に読み込み、envで明示された値で上書きする
         let mut env = HashMap::new();
+        if let Some(env_files) = &hook.env_file {
+            for env_file in env_files {
+                let path = self.resolve_worktree_path(env_file, worktree_path, branch, repo_root, "env_file")?;
+                env.extend(Self::load_env_file(&path)?);
+            }
+        }
         if let Some(hook_env) = &hook.env {
             for (key, value) in hook_env {
-                env.insert(key.clone(), self.expand_variables(value, worktree_path, branch));
+                env.insert(key.clone(), self.expand_variables(value, worktree_path, branch, repo_root));
             }
         }
         
-        match self.execute_shell_command(&expanded_command, worktree_path, &env) {
-            Ok(_) => {
-                println!("✓ Executed: {}", expanded_command);
+        let cache_key = self.cache_key(hook, command, worktree_path, branch, repo_root)?;
+        if let (Some(cache), Some((hook_key, inputs_hash, outputs))) = (&self.cache, &cache_key) {
+            match cache.lookup(hook_key, inputs_hash, worktree_path, outputs)? {
+                CacheOutcome::UpToDate => {
+                    output::progress(format!("✓ Skipped (inputs unchanged): {}", expanded_command));
+                    return Ok(HookRunStatus::Skipped);
+                }
+                CacheOutcome::Restored => {
+                    cache.record(hook_key, inputs_hash, worktree_path)?;
+                    output::progress(format!("✓ Restored from cache: {}", expanded_command));
+                    return Ok(HookRunStatus::Restored);
+                }
+                CacheOutcome::Miss => {}
             }
-            Err(e) => {
-                return Err(GitGardenerError::Custom(
-                    format!("Command failed: {}", e)
-                ));
+        }
+        
+        self.execute_shell_command(&expanded_command, &working_dir, &env, log_path)?;
+        output::progress(format!("✓ Executed: {}", expanded_command));
+        
+        if let (Some(cache), Some((hook_key, inputs_hash, outputs))) = (&self.cache, &cache_key) {
+            cache.store(hook_key, inputs_hash, worktree_path, outputs)?;
+        }
+        
+        Ok(HookRunStatus::Success)
+    }
+    
+    // 履歴や一覧に表示するフックの説明
+    fn describe_hook(&self, hook: &Hook, worktree_path: &Path, branch: &str, repo_root: &Path) -> String {
+        match &hook.hook_type {
+            crate::config::HookType::Copy => format!(
+                "copy {} -> {}",
+                hook.from.as_deref().unwrap_or("?"),
+                hook.to.as_deref().unwrap_or("?")
+            ),
+            crate::config::HookType::Command => hook
+                .command
+                .as_deref()
+                .map(|command| self.expand_variables(command, worktree_path, branch, repo_root))
+                .unwrap_or_else(|| "(missing command)".to_string()),
+        }
+    }
+    
+    // コマンドフックの出力を保存するログファイル（<state dir>/logs/<branch>/<時刻>-<番号>.log）
+    fn log_path(&self, branch: &str, started_at: i64, index: usize) -> Option<PathBuf> {
+        let state_dir = self.state_dir.as_ref()?;
+        Some(state::log_dir(state_dir, branch).join(format!("{}-{}.log", started_at, index)))
+    }
+    
+    // inputsを宣言したフックについて（フック識別子, 入力ハッシュ, 出力パス）を求める
+    fn cache_key(
+        &self,
+        hook: &Hook,
+        command: &str,
+        worktree_path: &Path,
+        branch: &str,
+        repo_root: &Path,
+    ) -> Result<Option<(String, String, Vec<PathBuf>)>> {
+        let Some(inputs) = &hook.inputs else {
+            return Ok(None);
+        };
+        if self.cache.is_none() {
+            return Ok(None);
+        }
+        
+        let outputs = hook.outputs.clone().unwrap_or_default();
+        let env_files = hook.env_file.clone().unwrap_or_default();
+        let hook_key = HookCache::hook_key(
+            command,
+            hook.cwd.as_deref(),
+            hook.env.as_ref(),
+            &env_files,
+            inputs,
+            &outputs,
+        )?;
+        
+        // env_fileの内容が変わった場合も実行し直す
+        let input_paths = inputs
+            .iter()
+            .chain(&env_files)
+            .map(|input| self.resolve_worktree_path(input, worktree_path, branch, repo_root, "inputs"))
+            .collect::<Result<Vec<_>>>()?;
+        let output_paths = outputs
+            .iter()
+            .map(|output| self.resolve_worktree_path(output, worktree_path, branch, repo_root, "outputs"))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let inputs_hash = HookCache::inputs_hash(&hook_key, worktree_path, &input_paths)?;
+        Ok(Some((hook_key, inputs_hash, output_paths)))
+    }
+    
+    fn validate_hook(&self, hook: &Hook, worktree_path: &Path, branch: &str, repo_root: &Path) -> Result<()> {
+        if let Some(cwd) = &hook.cwd {
+            self.resolve_worktree_path(cwd, worktree_path, branch, repo_root, "cwd")?;
+        }
+        
+        let path_fields = [
+            ("env_file", &hook.env_file),
+            ("inputs", &hook.inputs),
+            ("outputs", &hook.outputs),
+        ];
+        for (field, paths) in path_fields {
+            for path in paths.iter().flatten() {
+                self.resolve_worktree_path(path, worktree_path, branch, repo_root, field)?;
             }
         }
         
+        if hook.outputs.is_some() && hook.inputs.is_none() {
+            return Err(GitGardenerError::InvalidConfig {
+                message: "Hook 'outputs' requires 'inputs' to be declared".to_string(),
+            });
+        }
+        
         Ok(())
     }
     
-    fn expand_variables(&self, command: &str, worktree_path: &Path, branch: &str) -> String {
+    // フック設定のパスを展開してworktree内の絶対パスに解決する（worktree外を指す場合はエラー）
+    fn resolve_worktree_path(
+        &self,
+        raw: &str,
+        worktree_path: &Path,
+        branch: &str,
+        repo_root: &Path,
+        field: &str,
+    ) -> Result<PathBuf> {
+        let expanded = self.expand_variables(raw, worktree_path, branch, repo_root);
+        let root = normalize_path(worktree_path);
+        let resolved = normalize_path(&root.join(&expanded));
+        
+        // シンボリックリンクで外に出られないよう、実体のパスでも確認する
+        let canonical_root = canonicalize_existing(&root);
+        if !resolved.starts_with(&root) || !canonicalize_existing(&resolved).starts_with(&canonical_root) {
+            return Err(GitGardenerError::InvalidConfig {
+                message: format!("Hook {} '{}' escapes the worktree", field, raw),
+            });
+        }
+        
+        Ok(resolved)
+    }
+    
+    fn load_env_file(path: &Path) -> Result<HashMap<String, String>> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            GitGardenerError::Custom(format!("Failed to read env file {}: {}", path.display(), e))
+        })?;
+        
+        Ok(parse_env_file(&contents))
+    }
+    
+    // ${REPO_ROOT}に入れるパス（メインworktree、解決できなければ親ディレクトリ）
+    fn repo_root(worktree_path: &Path) -> PathBuf {
+        GitWorktree::from_path(worktree_path)
+            .and_then(|git_worktree| git_worktree.get_repository_root())
+            .unwrap_or_else(|_| worktree_path.parent().unwrap_or(worktree_path).to_path_buf())
+    }
+    
+    fn expand_variables(&self, command: &str, worktree_path: &Path, branch: &str, repo_root: &Path) -> String {
         // 環境変数を展開
         command
             .replace("${WORKTREE_PATH}", &worktree_path.display().to_string())
             .replace("${BRANCH}", branch)
-            .replace("${REPO_ROOT}", &worktree_path.parent().unwrap_or(worktree_path).display().to_string())
+            .replace("${REPO_ROOT}", &repo_root.display().to_string())
     }
     
-    fn execute_shell_command(&self, command: &str, working_dir: &Path, env: &HashMap<String, String>) -> Result<()> {
+    fn execute_shell_command(
+        &self,
+        command: &str,
+        working_dir: &Path,
+        env: &HashMap<String, String>,
+        log_path: Option<&Path>,
+    ) -> Result<()> {
         // POSIXシェルでコマンドを実行
         let mut cmd = if cfg!(target_os = "windows") {
             let mut cmd = Command::new("cmd");
-            cmd.args(&["/C", command]);
+            cmd.args(["/C", command]);
             cmd
         } else {
             let mut cmd = Command::new("sh");
-            cmd.args(&["-c", command]);
+            cmd.args(["-c", command]);
             cmd
         };
         
-        // ワーキングディレクトリが存在する場合のみ設定
-        if working_dir.exists() {
-            cmd.current_dir(working_dir);
+        // 別の場所で実行されないよう、ワーキングディレクトリがなければエラーにする
+        if !working_dir.is_dir() {
+            return Err(GitGardenerError::Custom(
+                format!("Hook working directory does not exist: {}", working_dir.display())
+            ));
         }
+        cmd.current_dir(working_dir);
         
         // 環境変数を設定
         cmd.envs(env);
         
-        let output = cmd.output()?;
+        let output = Self::run_interruptible(&mut cmd, self.live_output)?;
+        
+        // 標準出力と標準エラーをログに残す
+        if let Some(log_path) = log_path {
+            if let Some(parent) = log_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut log = format!("$ {}\n", command).into_bytes();
+            log.extend_from_slice(&output.stdout);
+            log.extend_from_slice(&output.stderr);
+            std::fs::write(log_path, log)?;
+        }
         
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(GitGardenerError::Custom(
-                format!("Command failed with exit code {:?}: {}", output.status.code(), stderr)
-            ));
+            return Err(GitGardenerError::HookFailed {
+                command: command.to_string(),
+                exit_code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+            });
         }
         
         Ok(())
     }
     
+    // 子プロセスを独自のプロセスグループで起動し、シグナル受信時はグループごと終了させる。
+    // live_outputの場合は端末から入力を読めるよう、フォアグラウンドのプロセスグループに残す
+    // （Ctrl-Cは端末から直接届く）
+    fn run_interruptible(cmd: &mut Command, live_output: bool) -> Result<Output> {
+        #[cfg(unix)]
+        if !live_output {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        
+        let stdin = if live_output { Stdio::inherit() } else { Stdio::null() };
+        let mut child = cmd
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        
+        // パイプが詰まらないよう、待機中も別スレッドで出力を読み続ける
+        let stdout_reader = child.stdout.take().map(|pipe| spawn_reader(pipe, live_output.then_some(Echo::Stdout)));
+        let stderr_reader = child.stderr.take().map(|pipe| spawn_reader(pipe, live_output.then_some(Echo::Stderr)));
+        
+        let status = signal::wait_interruptible(&mut child)?;
+        
+        Ok(Output {
+            status,
+            stdout: stdout_reader.map(join_reader).unwrap_or_default(),
+            stderr: stderr_reader.map(join_reader).unwrap_or_default(),
+        })
+    }
+}
+
+// 読み取った出力をそのまま表示する先
+#[derive(Clone, Copy)]
+enum Echo {
+    Stdout,
+    Stderr,
+}
+
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R, echo: Option<Echo>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let Some(echo) = echo else {
+            let _ = pipe.read_to_end(&mut buffer);
+            return buffer;
+        };
+        
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let _ = match echo {
+                        Echo::Stdout => write_flush(&mut std::io::stdout(), &chunk[..n]),
+                        Echo::Stderr => write_flush(&mut std::io::stderr(), &chunk[..n]),
+                    };
+                    buffer.extend_from_slice(&chunk[..n]);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+        buffer
+    })
+}
+
+fn write_flush(writer: &mut impl std::io::Write, bytes: &[u8]) -> std::io::Result<()> {
+    writer.write_all(bytes)?;
+    writer.flush()
+}
+
+fn join_reader(handle: std::thread::JoinHandle<Vec<u8>>) -> Vec<u8> {
+    handle.join().unwrap_or_default()
+}
+
+// dotenv形式（KEY=VALUE、#コメント、export接頭辞、クォート）をパースする
+fn parse_env_file(contents: &str) -> HashMap<String, String> {
+    let mut env = HashMap::new();
+    
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        
+        let value = value.trim();
+        let value = if value.len() >= 2
+            && ((value.starts_with('"') && value.ends_with('"'))
+                || (value.starts_with('\'') && value.ends_with('\'')))
+        {
+            &value[1..value.len() - 1]
+        } else {
+            // クォートされていない値は行末コメントを取り除く
+            value.split(" #").next().unwrap_or(value).trim_end()
+        };
+        
+        env.insert(key.to_string(), value.to_string());
+    }
+    
+    env
+}
+
+// ファイルシステムに触れずに `.` と `..` を解決する
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    
+    normalized
 }
 
 #[cfg(test)]
@@ -151,6 +591,7 @@ mod tests {
             to: Some("dest.txt".to_string()),
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
@@ -177,6 +618,7 @@ mod tests {
             to: Some("dest.txt".to_string()),
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
@@ -198,6 +640,7 @@ mod tests {
             to: None,
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
@@ -219,6 +662,7 @@ mod tests {
             to: Some("dest.txt".to_string()),
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
@@ -241,10 +685,11 @@ mod tests {
             to: None,
             command: Some("echo 'test' > test.txt".to_string()),
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
-        let result = executor.execute_command_hook(&hook, &worktree_path, "test-branch");
+        let result = executor.execute_command_hook(&hook, &worktree_path, "test-branch", temp_dir.path(), None);
         
         assert!(result.is_ok());
         
@@ -253,6 +698,24 @@ mod tests {
         assert!(test_file.exists());
     }
 
+    #[test]
+    fn test_execute_command_hook_fails_for_missing_worktree() {
+        // What: worktreeのディレクトリがない場合に別の場所で実行せずエラーになるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("missing");
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("echo 'test' > test.txt".to_string()),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_command_hook(&hook, &worktree_path, "test-branch", temp_dir.path(), None);
+        
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_execute_command_hook_fails_without_command_field() {
         // What: commandフィールドがないcommandフックがエラーになるかテスト
@@ -265,10 +728,11 @@ mod tests {
             to: None,
             command: None,
             env: None,
+            ..Default::default()
         };
         
         let executor = HookExecutor::new();
-        let result = executor.execute_command_hook(&hook, &worktree_path, "test-branch");
+        let result = executor.execute_command_hook(&hook, &worktree_path, "test-branch", temp_dir.path(), None);
         
         assert!(result.is_err());
         assert!(matches!(result.unwrap_err(), GitGardenerError::Custom(_)));
@@ -281,11 +745,13 @@ mod tests {
         let worktree_path = temp_dir.path().join("worktree");
         
         let executor = HookExecutor::new();
-        let command = "echo '${BRANCH}' '${WORKTREE_PATH}'";
-        let expanded = executor.expand_variables(command, &worktree_path, "feature-test");
+        let command = "echo '${BRANCH}' '${WORKTREE_PATH}' '${REPO_ROOT}'";
+        let expanded = executor.expand_variables(command, &worktree_path, "feature-test", temp_dir.path());
         
-        assert!(expanded.contains("feature-test"));
-        assert!(expanded.contains(&worktree_path.display().to_string()));
+        assert_eq!(
+            expanded,
+            format!("echo 'feature-test' '{}' '{}'", worktree_path.display(), temp_dir.path().display())
+        );
     }
 
     #[test]
@@ -306,6 +772,7 @@ mod tests {
                 to: Some("copied.txt".to_string()),
                 command: None,
                 env: None,
+                ..Default::default()
             },
             Hook {
                 hook_type: HookType::Command,
@@ -313,6 +780,7 @@ mod tests {
                 to: None,
                 command: Some("echo 'command executed' > executed.txt".to_string()),
                 env: None,
+                ..Default::default()
             },
         ];
         
@@ -325,4 +793,286 @@ mod tests {
         assert!(worktree_path.join("copied.txt").exists());
         assert!(worktree_path.join("executed.txt").exists());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_execute_command_hook_runs_in_cwd() {
+        // What: cwdを指定したcommandフックがそのディレクトリで実行されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(worktree_path.join("packages/api")).unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("echo 'here' > cwd.txt".to_string()),
+            cwd: Some("packages/api".to_string()),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &[hook]);
+        
+        assert!(result.is_ok());
+        assert!(worktree_path.join("packages/api/cwd.txt").exists());
+        assert!(!worktree_path.join("cwd.txt").exists());
+    }
+
+    #[test]
+    fn test_execute_hooks_rejects_cwd_outside_worktree() {
+        // What: worktree外を指すcwdが検証で拒否され、どのフックも実行されないかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        
+        let hooks = vec![
+            Hook {
+                hook_type: HookType::Command,
+                command: Some("echo 'first' > first.txt".to_string()),
+                ..Default::default()
+            },
+            Hook {
+                hook_type: HookType::Command,
+                command: Some("true".to_string()),
+                cwd: Some("../..".to_string()),
+                ..Default::default()
+            },
+        ];
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &hooks);
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidConfig { .. }));
+        assert!(!worktree_path.join("first.txt").exists());
+    }
+
+    #[test]
+    fn test_execute_hooks_rejects_env_file_outside_worktree() {
+        // What: worktree外を指すenv_fileが検証で拒否されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("true".to_string()),
+            env_file: Some(vec!["/etc/environment".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &[hook]);
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidConfig { .. }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_hooks_rejects_symlink_escaping_worktree() {
+        // What: worktree外を指すシンボリックリンク経由のパスが拒否されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join(".env"), "SECRET=1\n").unwrap();
+        std::os::unix::fs::symlink(&outside, worktree_path.join("link")).unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("true".to_string()),
+            env_file: Some(vec!["link/.env".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &[hook]);
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn test_execute_command_hook_loads_env_file() {
+        // What: env_fileの値が読み込まれ、envの値で上書きされるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::write(
+            worktree_path.join(".env"),
+            "# comment\nexport DB_NAME=app_dev\nAPI_KEY=\"secret value\"\nPORT=3000\n",
+        ).unwrap();
+        
+        let mut env = HashMap::new();
+        env.insert("PORT".to_string(), "4000".to_string());
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("echo \"$DB_NAME|$API_KEY|$PORT\" > env.txt".to_string()),
+            env: Some(env),
+            env_file: Some(vec![".env".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &[hook]);
+        
+        assert!(result.is_ok());
+        let content = fs::read_to_string(worktree_path.join("env.txt")).unwrap();
+        assert_eq!(content.trim(), "app_dev|secret value|4000");
+    }
+
+    #[test]
+    fn test_parse_env_file_handles_comments_and_quotes() {
+        // What: dotenvパーサがコメント・export・クォートを正しく扱うかテスト
+        let env = parse_env_file("A=1\n# skip\n\nexport B='two words'\nC=3 # trailing\ninvalid line\n");
+        
+        assert_eq!(env.get("A"), Some(&"1".to_string()));
+        assert_eq!(env.get("B"), Some(&"two words".to_string()));
+        assert_eq!(env.get("C"), Some(&"3".to_string()));
+        assert_eq!(env.len(), 3);
+    }
+
+    #[test]
+    fn test_execute_hooks_skips_when_inputs_unchanged() {
+        // What: 入力が前回と同じ場合にフックがスキップされ、変わると再実行されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        fs::write(worktree_path.join("package-lock.json"), "v1").unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("echo run >> runs.txt".to_string()),
+            inputs: Some(vec!["package-lock.json".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::with_state_dir(&state_dir);
+        executor.execute_hooks(&worktree_path, "test-branch", std::slice::from_ref(&hook)).unwrap();
+        executor.execute_hooks(&worktree_path, "test-branch", std::slice::from_ref(&hook)).unwrap();
+        
+        let runs = fs::read_to_string(worktree_path.join("runs.txt")).unwrap();
+        assert_eq!(runs.lines().count(), 1);
+        
+        fs::write(worktree_path.join("package-lock.json"), "v2").unwrap();
+        executor.execute_hooks(&worktree_path, "test-branch", &[hook]).unwrap();
+        
+        let runs = fs::read_to_string(worktree_path.join("runs.txt")).unwrap();
+        assert_eq!(runs.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_execute_hooks_restores_outputs_from_cache() {
+        // What: 別のworktreeで同じ入力から作った出力がキャッシュから復元されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        for worktree in [&first, &second] {
+            fs::create_dir_all(worktree).unwrap();
+            fs::write(worktree.join("package-lock.json"), "same").unwrap();
+        }
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("mkdir -p deps && echo built > deps/lib.txt && echo run >> runs.txt".to_string()),
+            inputs: Some(vec!["package-lock.json".to_string()]),
+            outputs: Some(vec!["deps".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::with_state_dir(&state_dir);
+        executor.execute_hooks(&first, "first", std::slice::from_ref(&hook)).unwrap();
+        executor.execute_hooks(&second, "second", &[hook]).unwrap();
+        
+        assert!(first.join("runs.txt").exists());
+        assert!(!second.join("runs.txt").exists());
+        let content = fs::read_to_string(second.join("deps/lib.txt")).unwrap();
+        assert_eq!(content.trim(), "built");
+    }
+
+    #[test]
+    fn test_execute_hooks_rejects_outputs_without_inputs() {
+        // What: inputsなしでoutputsを宣言したフックが検証で拒否されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("true".to_string()),
+            outputs: Some(vec!["node_modules".to_string()]),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::new();
+        let result = executor.execute_hooks(&worktree_path, "test-branch", &[hook]);
+        
+        assert!(matches!(result.unwrap_err(), GitGardenerError::InvalidConfig { .. }));
+    }
+
+    #[test]
+    fn test_execute_hooks_records_history_and_logs() {
+        // What: フックの実行結果・所要時間・ログが履歴に記録されるかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        
+        let hooks = vec![
+            Hook {
+                hook_type: HookType::Command,
+                command: Some("echo hello-from-hook".to_string()),
+                ..Default::default()
+            },
+            Hook {
+                hook_type: HookType::Command,
+                command: Some("exit 3".to_string()),
+                ..Default::default()
+            },
+        ];
+        
+        let executor = HookExecutor::with_state_dir(&state_dir);
+        let result = executor.execute_hooks(&worktree_path, "feature/history", &hooks);
+        
+        assert!(matches!(
+            result.unwrap_err(),
+            GitGardenerError::HookFailed { exit_code: Some(3), .. }
+        ));
+        
+        let history = HookHistory::load(&state_dir).unwrap();
+        assert_eq!(history.runs.len(), 2);
+        assert_eq!(history.runs[0].worktree, "feature/history");
+        assert_eq!(history.runs[0].status, HookRunStatus::Success);
+        assert_eq!(history.runs[1].status, HookRunStatus::Failed);
+        assert_eq!(history.runs[1].exit_code, Some(3));
+        
+        let log = fs::read_to_string(history.runs[0].log_path.as_ref().unwrap()).unwrap();
+        assert!(log.contains("hello-from-hook"));
+    }
+
+    #[test]
+    fn test_live_output_still_logs_and_reports_stderr() {
+        // What: 出力をその場で表示する場合も、ログと失敗時の標準エラーが残るかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().join("state");
+        let worktree_path = temp_dir.path().join("worktree");
+        fs::create_dir_all(&worktree_path).unwrap();
+        
+        let hook = Hook {
+            hook_type: HookType::Command,
+            command: Some("echo live-stdout; echo live-stderr >&2; exit 2".to_string()),
+            ..Default::default()
+        };
+        
+        let executor = HookExecutor::with_state_dir(&state_dir).with_live_output();
+        let result = executor.execute_hooks(&worktree_path, "live", &[hook]);
+        
+        assert!(matches!(
+            result.unwrap_err(),
+            GitGardenerError::HookFailed { exit_code: Some(2), ref stderr, .. } if stderr == "live-stderr"
+        ));
+        let history = HookHistory::load(&state_dir).unwrap();
+        let log = fs::read_to_string(history.runs[0].log_path.as_ref().unwrap()).unwrap();
+        assert!(log.contains("live-stdout") && log.contains("live-stderr"));
+    }
+}
@@ -1,5 +1,15 @@
 pub mod error;
 pub mod hooks;
+pub mod cache;
 pub mod config;
 pub mod git;
-pub mod commands;
\ No newline at end of file
+pub mod signal;
+pub mod state;
+pub mod time;
+pub mod output;
+pub mod disk_usage;
+pub mod template;
+pub mod commands;
+
+#[cfg(test)]
+mod test_support;
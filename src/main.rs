@@ -1,19 +1,20 @@
 mod cli;
-mod commands;
-mod config;
-mod error;
-mod git;
-mod hooks;
 
 use clap::Parser;
-use cli::{Cli, Commands};
-use commands::{add::AddCommand, cd::CdCommand, completion::CompletionCommand, init::InitCommand, list::ListCommand, remove::RemoveCommand, shell_init::ShellInitCommand};
-use error::Result;
+use cli::{Cli, Commands, HooksCommands};
+use git_gardener::commands::{add::AddCommand, cd::CdCommand, clean::CleanCommand, completion::CompletionCommand, du::DuCommand, hooks::HooksHistoryCommand, init::InitCommand, list::{ListCommand, ListFilter, ListFormat}, lock::{LockCommand, UnlockCommand}, mv::MoveCommand, prompt::PromptCommand, prune::PruneCommand, remove::RemoveCommand, rename::RenameCommand, run::RunCommand, shell_init::ShellInitCommand};
+use git_gardener::error::{GitGardenerError, Result};
+use git_gardener::output::OutputFormat;
 
 fn main() {
     if let Err(e) = run() {
         eprintln!("Error: {}", e);
-        std::process::exit(1);
+        // シグナルで中断した場合はシェルの慣例どおり 128 + シグナル番号で終了する
+        let code = match e {
+            GitGardenerError::Interrupted { signal } => 128 + signal,
+            _ => 1,
+        };
+        std::process::exit(code);
     }
 }
 
@@ -29,12 +30,44 @@ fn run() -> Result<()> {
             branch,
             new_branch,
             commit,
+            detach,
+            remote,
+            fetch,
+            format,
         } => {
-            let cmd = AddCommand::new(branch, new_branch, commit);
+            let cmd = AddCommand::new(branch, new_branch, commit, detach, remote, fetch).with_format(format);
             cmd.execute()
         }
-        Commands::List { names_only } => {
-            let cmd = ListCommand::new(names_only);
+        Commands::List {
+            names_only,
+            porcelain,
+            format,
+            refresh,
+            columns,
+            dirty,
+            clean,
+            merged,
+            stale,
+            locked,
+            pattern,
+            sort,
+            tree,
+        } => {
+            // --porcelainは--format porcelainの省略形
+            let format = if porcelain { ListFormat::Builtin(OutputFormat::Porcelain) } else { ListFormat::parse(&format) };
+            let filter = ListFilter { dirty, clean, merged, stale, locked, pattern };
+            let cmd = ListCommand::new(names_only, format, refresh, columns)
+                .with_filter(filter)
+                .with_sort(sort)
+                .with_tree(tree);
+            cmd.execute()
+        }
+        Commands::Prompt { refresh } => {
+            let cmd = PromptCommand::new(refresh);
+            cmd.execute()
+        }
+        Commands::Du { worktrees, ignored, depth, format } => {
+            let cmd = DuCommand::new(worktrees, ignored, depth).with_format(format);
             cmd.execute()
         }
         Commands::Cd { worktree } => {
@@ -43,10 +76,44 @@ fn run() -> Result<()> {
             println!("{}", path);
             Ok(())
         }
-        Commands::Remove { worktree, with_branch } => {
-            let cmd = RemoveCommand::new(worktree, with_branch);
+        Commands::Remove { worktree, with_branch, force, format } => {
+            let cmd = RemoveCommand::new(worktree, with_branch, force).with_format(format);
+            cmd.execute()
+        }
+        Commands::Prune { dry_run, with_branch, force, format } => {
+            let cmd = PruneCommand::new(dry_run, with_branch, force).with_format(format);
+            cmd.execute()
+        }
+        Commands::Clean { merged, into, stale, dry_run, yes, with_branch, format } => {
+            let cmd = CleanCommand::new(merged, into, stale, dry_run, yes, with_branch).with_format(format);
+            cmd.execute()
+        }
+        Commands::Lock { worktree, reason } => {
+            let cmd = LockCommand::new(worktree, reason);
+            cmd.execute()
+        }
+        Commands::Unlock { worktree } => {
+            let cmd = UnlockCommand::new(worktree);
+            cmd.execute()
+        }
+        Commands::Move { worktree, new_path } => {
+            let cmd = MoveCommand::new(worktree, new_path);
+            cmd.execute()
+        }
+        Commands::Rename { worktree, new_branch } => {
+            let cmd = RenameCommand::new(worktree, new_branch);
+            cmd.execute()
+        }
+        Commands::Run { task, worktree, all } => {
+            let cmd = RunCommand::new(task, worktree, all);
             cmd.execute()
         }
+        Commands::Hooks { command } => match command {
+            HooksCommands::History { worktree, limit } => {
+                let cmd = HooksHistoryCommand::new(worktree, limit);
+                cmd.execute()
+            }
+        },
         Commands::Completion { shell } => {
             let cmd = CompletionCommand::new(shell);
             cmd.execute()
@@ -0,0 +1,262 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::error::{GitGardenerError, Result};
+
+// 構造化出力（json/yaml/porcelain）のバージョン。フィールドの削除や意味の変更時にだけ上げる
+pub const FORMAT_VERSION: u32 = 1;
+
+// 構造化出力のときは標準出力を結果だけにするため、進捗メッセージを標準エラーに出す
+static PROGRESS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Default)]
+pub enum OutputFormat {
+    // 人が読むための表示
+    #[default]
+    Text,
+    Json,
+    Yaml,
+    Tsv,
+    // `key value`の行を空行で区切ったレコード（先頭に`version N`）
+    Porcelain,
+}
+
+impl OutputFormat {
+    pub fn is_structured(&self) -> bool {
+        *self != OutputFormat::Text
+    }
+}
+
+// コマンドの出力形式に合わせて進捗メッセージの出力先を切り替える
+pub fn init(format: OutputFormat) {
+    PROGRESS_TO_STDERR.store(format.is_structured(), Ordering::SeqCst);
+}
+
+pub fn progress(message: impl Display) {
+    if PROGRESS_TO_STDERR.load(Ordering::SeqCst) {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+// add/remove/prune/cleanで行った（dry-runでは行う予定の）変更
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WorktreeChange {
+    pub action: ChangeAction,
+    pub name: String,
+    pub path: PathBuf,
+    // detached HEADの場合はNone
+    pub branch: Option<String>,
+    // prune/cleanの対象になった理由、またはスキップした理由
+    pub reason: Option<String>,
+    pub branch_created: bool,
+    pub branch_removed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeAction {
+    Created,
+    Removed,
+    Pruned,
+    Skipped,
+}
+
+impl Display for ChangeAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ChangeAction::Created => "created",
+            ChangeAction::Removed => "removed",
+            ChangeAction::Pruned => "pruned",
+            ChangeAction::Skipped => "skipped",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Serialize)]
+struct ChangeReport<'a> {
+    version: u32,
+    command: &'a str,
+    dry_run: bool,
+    changes: &'a [WorktreeChange],
+}
+
+// 構造化出力の場合に変更の一覧を出力する（テキストの場合は進捗メッセージで済んでいるので何もしない）
+pub fn print_changes(format: OutputFormat, command: &str, dry_run: bool, changes: &[WorktreeChange]) -> Result<()> {
+    if format.is_structured() {
+        print!("{}", format_changes(format, command, dry_run, changes)?);
+    }
+    Ok(())
+}
+
+pub fn format_changes(format: OutputFormat, command: &str, dry_run: bool, changes: &[WorktreeChange]) -> Result<String> {
+    let report = ChangeReport {
+        version: FORMAT_VERSION,
+        command,
+        dry_run,
+        changes,
+    };
+
+    match format {
+        OutputFormat::Text => Ok(String::new()),
+        OutputFormat::Json => to_json(&report),
+        OutputFormat::Yaml => to_yaml(&report),
+        OutputFormat::Tsv => Ok(format_tsv(
+            &["action", "name", "branch", "path", "reason", "branch_created", "branch_removed", "dry_run"],
+            changes.iter().map(|change| {
+                vec![
+                    change.action.to_string(),
+                    change.name.clone(),
+                    change.branch.clone().unwrap_or_default(),
+                    change.path.display().to_string(),
+                    change.reason.clone().unwrap_or_default(),
+                    change.branch_created.to_string(),
+                    change.branch_removed.to_string(),
+                    dry_run.to_string(),
+                ]
+            }),
+        )),
+        OutputFormat::Porcelain => {
+            let mut output = porcelain_header();
+            for change in changes {
+                output.push_str(&format!("{} {}\n", change.action, escape(&change.path.display().to_string())));
+                output.push_str(&format!("name {}\n", escape(&change.name)));
+                if let Some(branch) = &change.branch {
+                    output.push_str(&format!("branch {}\n", escape(branch)));
+                }
+                if let Some(reason) = &change.reason {
+                    output.push_str(&format!("reason {}\n", escape(reason)));
+                }
+                if change.branch_created {
+                    output.push_str("branch-created\n");
+                }
+                if change.branch_removed {
+                    output.push_str("branch-removed\n");
+                }
+                if dry_run {
+                    output.push_str("dry-run\n");
+                }
+                output.push('\n');
+            }
+            Ok(output)
+        }
+    }
+}
+
+pub fn porcelain_header() -> String {
+    format!("version {}\n\n", FORMAT_VERSION)
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| GitGardenerError::Custom(format!("Failed to serialize output: {}", e)))?;
+    Ok(format!("{}\n", json))
+}
+
+pub fn to_yaml<T: Serialize>(value: &T) -> Result<String> {
+    serde_yaml::to_string(value).map_err(|e| GitGardenerError::Custom(format!("Failed to serialize output: {}", e)))
+}
+
+// ヘッダー行付きのタブ区切り（値の中のタブ・改行・バックスラッシュはエスケープする）
+pub fn format_tsv(header: &[&str], rows: impl Iterator<Item = Vec<String>>) -> String {
+    let mut output = header.join("\t");
+    output.push('\n');
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| escape(cell)).collect();
+        output.push_str(&cells.join("\t"));
+        output.push('\n');
+    }
+    output
+}
+
+// tsv・porcelainの値を1行に収める（パスやロックの理由に含まれる改行で形式が崩れないようにする）
+pub fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(action: ChangeAction, branch: Option<&str>) -> WorktreeChange {
+        WorktreeChange {
+            action,
+            name: "feature-x".to_string(),
+            path: PathBuf::from("/repo/.gardener/feature/x"),
+            branch: branch.map(|b| b.to_string()),
+            reason: Some("directory missing".to_string()),
+            branch_created: false,
+            branch_removed: true,
+        }
+    }
+
+    #[test]
+    fn test_format_changes_porcelain_and_tsv() {
+        // What: 変更一覧がバージョン付きのporcelainと、ヘッダー付きのTSVで出力されるかテスト
+        let changes = vec![change(ChangeAction::Pruned, Some("feature/x")), change(ChangeAction::Skipped, None)];
+
+        let porcelain = format_changes(OutputFormat::Porcelain, "prune", false, &changes).unwrap();
+        let tsv = format_changes(OutputFormat::Tsv, "prune", true, &changes[..1]).unwrap();
+
+        assert_eq!(
+            porcelain,
+            "version 1\n\n\
+             pruned /repo/.gardener/feature/x\nname feature-x\nbranch feature/x\nreason directory missing\nbranch-removed\n\n\
+             skipped /repo/.gardener/feature/x\nname feature-x\nreason directory missing\nbranch-removed\n\n"
+        );
+        assert_eq!(
+            tsv,
+            "action\tname\tbranch\tpath\treason\tbranch_created\tbranch_removed\tdry_run\n\
+             pruned\tfeature-x\tfeature/x\t/repo/.gardener/feature/x\tdirectory missing\tfalse\ttrue\ttrue\n"
+        );
+    }
+
+    #[test]
+    fn test_format_changes_porcelain_escapes_values() {
+        // What: porcelainの値に含まれる改行がエスケープされ、レコードが崩れないかテスト
+        let changes = vec![WorktreeChange {
+            path: PathBuf::from("/repo/odd\nname"),
+            reason: Some("locked".to_string()),
+            ..change(ChangeAction::Skipped, None)
+        }];
+
+        let porcelain = format_changes(OutputFormat::Porcelain, "clean", false, &changes).unwrap();
+
+        assert_eq!(
+            porcelain,
+            "version 1\n\nskipped /repo/odd\\nname\nname feature-x\nreason locked\nbranch-removed\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_changes_json_and_yaml() {
+        // What: JSON・YAMLの出力にバージョン・コマンド名・変更内容が含まれるかテスト
+        let changes = vec![change(ChangeAction::Removed, None)];
+
+        let json = format_changes(OutputFormat::Json, "clean", false, &changes).unwrap();
+        let yaml = format_changes(OutputFormat::Yaml, "clean", false, &changes).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["command"], "clean");
+        assert_eq!(value["changes"][0]["action"], "removed");
+        assert!(value["changes"][0]["branch"].is_null());
+        assert!(yaml.contains("action: removed"));
+    }
+
+    #[test]
+    fn test_format_tsv_escapes_values() {
+        // What: TSVの値に含まれるタブや改行がエスケープされるかテスト
+        let tsv = format_tsv(&["a", "b"], vec![vec!["x\ty".to_string(), "line\nbreak\\".to_string()]].into_iter());
+
+        assert_eq!(tsv, "a\tb\nx\\ty\tline\\nbreak\\\\\n");
+    }
+}
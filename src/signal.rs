@@ -0,0 +1,239 @@
+use crate::error::{GitGardenerError, Result};
+use std::process::{Child, ExitStatus};
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// 受信したシグナル番号（0は未受信）
+static RECEIVED: AtomicI32 = AtomicI32::new(0);
+// 有効なSignalGuardの数（最も外側のガードでのみ受信状態をリセットする）
+static DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+// SIGKILLを送る前に、フックのプロセスグループが終了するのを待つ時間
+pub const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[cfg(unix)]
+pub const SIGINT: i32 = libc::SIGINT;
+#[cfg(not(unix))]
+pub const SIGINT: i32 = 2;
+
+// 生存中はSIGINT/SIGTERMでプロセスを終了させず、受信したことだけを記録する
+pub struct SignalGuard {
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+}
+
+impl SignalGuard {
+    pub fn install() -> Self {
+        if DEPTH.fetch_add(1, Ordering::SeqCst) == 0 {
+            RECEIVED.store(0, Ordering::SeqCst);
+        }
+
+        #[cfg(unix)]
+        {
+            let mut previous = Vec::new();
+            for signal in [libc::SIGINT, libc::SIGTERM] {
+                // SAFETY: sigactionは初期化済みの構造体に対してのみ呼び出し、
+                // ハンドラはアトミック変数への書き込みのみを行う（async-signal-safe）
+                unsafe {
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    action.sa_sigaction = record_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+                    action.sa_flags = libc::SA_RESTART;
+                    libc::sigemptyset(&mut action.sa_mask);
+
+                    let mut old: libc::sigaction = std::mem::zeroed();
+                    if libc::sigaction(signal, &action, &mut old) == 0 {
+                        previous.push((signal, old));
+                    }
+                }
+            }
+            Self { previous }
+        }
+
+        #[cfg(not(unix))]
+        Self {}
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        for (signal, old) in self.previous.drain(..) {
+            // SAFETY: installで保存した元のハンドラを戻すだけ
+            unsafe {
+                libc::sigaction(signal, &old, std::ptr::null_mut());
+            }
+        }
+
+        DEPTH.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn record_signal(signal: libc::c_int) {
+    RECEIVED.store(signal, Ordering::SeqCst);
+}
+
+// SignalGuardの有効中に受信したシグナル
+pub fn received() -> Option<i32> {
+    match RECEIVED.load(Ordering::SeqCst) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+pub fn signal_name(signal: i32) -> String {
+    #[cfg(unix)]
+    match signal {
+        libc::SIGINT => return "SIGINT".to_string(),
+        libc::SIGTERM => return "SIGTERM".to_string(),
+        _ => {}
+    }
+
+    format!("signal {}", signal)
+}
+
+// 子プロセスの終了を待つ。シグナルを受信したらプロセスグループ全体に転送し、
+// 猶予期間内に終了しなければSIGKILLで終了させてからInterruptedを返す
+pub fn wait_interruptible(child: &mut Child) -> Result<ExitStatus> {
+    wait_with(child, GRACE_PERIOD, received)
+}
+
+fn wait_with(
+    child: &mut Child,
+    grace: Duration,
+    interrupted: impl Fn() -> Option<i32>,
+) -> Result<ExitStatus> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+
+        if let Some(signal) = interrupted() {
+            terminate_process_group(child, signal, grace)?;
+            return Err(GitGardenerError::Interrupted { signal });
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn terminate_process_group(child: &mut Child, signal: i32, grace: Duration) -> Result<()> {
+    #[cfg(unix)]
+    {
+        // 独自のプロセスグループで起動していない場合（runのタスク）は子プロセスにだけ送る
+        let pid = child.id() as libc::pid_t;
+        // SAFETY: 自分で起動したプロセスかそのプロセスグループにのみシグナルを送る
+        let target = if unsafe { libc::getpgid(pid) } == pid { -pid } else { pid };
+        unsafe {
+            libc::kill(target, signal);
+        }
+
+        // リーダーが先に終了しても、終了処理中の子孫には猶予期間いっぱいまで待つ
+        let deadline = Instant::now() + grace;
+        let mut alive = true;
+        while alive && Instant::now() < deadline {
+            std::thread::sleep(POLL_INTERVAL);
+            let leader_exited = child.try_wait()?.is_some();
+            // SAFETY: シグナル0は送信せず、グループに残ったプロセスがあるかだけを調べる
+            alive = if target < 0 { (unsafe { libc::kill(target, 0) }) == 0 } else { !leader_exited };
+        }
+
+        if alive {
+            unsafe {
+                libc::kill(target, libc::SIGKILL);
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (signal, grace);
+        child.kill()?;
+    }
+
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    fn spawn_in_own_group(script: &str) -> Child {
+        Command::new("sh")
+            .args(["-c", script])
+            .process_group(0)
+            .spawn()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wait_with_returns_exit_status() {
+        // What: シグナルを受信していなければ子プロセスの終了ステータスを返すかテスト
+        let mut child = spawn_in_own_group("exit 3");
+
+        let status = wait_with(&mut child, Duration::from_millis(200), || None).unwrap();
+
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[test]
+    fn test_wait_with_forwards_signal_to_group() {
+        // What: シグナル受信時にプロセスグループへ転送し、Interruptedを返すかテスト
+        let mut child = spawn_in_own_group("sleep 30");
+        let started = Instant::now();
+
+        let result = wait_with(&mut child, Duration::from_secs(5), || Some(libc::SIGTERM));
+
+        assert!(matches!(
+            result.unwrap_err(),
+            GitGardenerError::Interrupted { signal } if signal == libc::SIGTERM
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_wait_with_kills_after_grace_period() {
+        // What: シグナルを無視するプロセスが猶予期間後にSIGKILLで終了させられるかテスト
+        let mut child = spawn_in_own_group("trap '' TERM; while true; do sleep 0.05; done");
+        std::thread::sleep(Duration::from_millis(100));
+        let started = Instant::now();
+
+        let result = wait_with(&mut child, Duration::from_millis(300), || Some(libc::SIGTERM));
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::Interrupted { .. }));
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(child.try_wait().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_wait_with_gives_descendants_the_grace_period() {
+        // What: リーダーが先に終了しても、終了処理中の子孫が猶予期間内は止められないかテスト
+        let temp_dir = tempfile::tempdir().unwrap();
+        let marker = temp_dir.path().join("cleaned-up");
+        let mut child = spawn_in_own_group(&format!(
+            "(trap 'sleep 0.2; touch {}; exit 0' TERM; while true; do sleep 0.05; done) & wait",
+            marker.display()
+        ));
+        std::thread::sleep(Duration::from_millis(100));
+        let started = Instant::now();
+
+        let result = wait_with(&mut child, Duration::from_secs(5), || Some(libc::SIGTERM));
+
+        assert!(matches!(result.unwrap_err(), GitGardenerError::Interrupted { .. }));
+        assert!(marker.exists());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_signal_name() {
+        // What: シグナル番号が名前に変換されるかテスト
+        assert_eq!(signal_name(libc::SIGINT), "SIGINT");
+        assert_eq!(signal_name(libc::SIGTERM), "SIGTERM");
+        assert_eq!(signal_name(64), "signal 64");
+    }
+}
@@ -0,0 +1,439 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::error::{GitGardenerError, Result};
+use crate::git::canonicalize_existing;
+
+// git-gardenerがリポジトリごとに保持する状態（<git common dir>/gardener/state.yml）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GardenerState {
+    #[serde(default)]
+    pub hook_cache: Vec<HookCacheEntry>,
+}
+
+// フックがどの入力ハッシュで実行されたかの記録
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HookCacheEntry {
+    pub hook: String,
+    pub worktree: PathBuf,
+    pub inputs_hash: String,
+}
+
+// フック実行履歴（<git common dir>/gardener/history.yml）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HookHistory {
+    #[serde(default)]
+    pub runs: Vec<HookRun>,
+}
+
+// 1回のフック実行の記録
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HookRun {
+    pub worktree: String,
+    pub worktree_path: PathBuf,
+    pub hook: String,
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub status: HookRunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookRunStatus {
+    Success,
+    Failed,
+    Skipped,
+    Restored,
+    Interrupted,
+}
+
+impl std::fmt::Display for HookRunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            HookRunStatus::Success => "success",
+            HookRunStatus::Failed => "failed",
+            HookRunStatus::Skipped => "skipped",
+            HookRunStatus::Restored => "restored",
+            HookRunStatus::Interrupted => "interrupted",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// 履歴として保持する最大件数（超えた分は古い順にログごと削除する）
+const MAX_HISTORY_RUNS: usize = 500;
+
+impl HookHistory {
+    pub fn load(state_dir: &Path) -> Result<Self> {
+        let path = Self::history_path(state_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&contents)
+            .map_err(|e| GitGardenerError::Custom(format!("Failed to parse hook history: {}", e)))
+    }
+
+    pub fn save(&self, state_dir: &Path) -> Result<()> {
+        let contents = serde_yaml::to_string(self)
+            .map_err(|e| GitGardenerError::Custom(format!("Failed to serialize hook history: {}", e)))?;
+        write_atomically(&Self::history_path(state_dir), &contents)
+    }
+
+    pub fn history_path(state_dir: &Path) -> PathBuf {
+        state_dir.join("history.yml")
+    }
+
+    pub fn append(state_dir: &Path, run: HookRun) -> Result<()> {
+        let _lock = StateLock::acquire(state_dir)?;
+        let mut history = Self::load(state_dir)?;
+        history.runs.push(run);
+
+        if history.runs.len() > MAX_HISTORY_RUNS {
+            let excess = history.runs.len() - MAX_HISTORY_RUNS;
+            for dropped in history.runs.drain(..excess) {
+                if let Some(log_path) = dropped.log_path {
+                    let _ = std::fs::remove_file(log_path);
+                }
+            }
+        }
+
+        history.save(state_dir)
+    }
+}
+
+impl GardenerState {
+    pub fn load(state_dir: &Path) -> Result<Self> {
+        let path = Self::state_path(state_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        serde_yaml::from_str(&contents)
+            .map_err(|e| GitGardenerError::Custom(format!("Failed to parse gardener state: {}", e)))
+    }
+
+    pub fn save(&self, state_dir: &Path) -> Result<()> {
+        let contents = serde_yaml::to_string(self)
+            .map_err(|e| GitGardenerError::Custom(format!("Failed to serialize gardener state: {}", e)))?;
+        write_atomically(&Self::state_path(state_dir), &contents)
+    }
+
+    pub fn state_path(state_dir: &Path) -> PathBuf {
+        state_dir.join("state.yml")
+    }
+
+    // 他のプロセスの書き込みを失わないよう、読み込みから保存までをロックの中で行う（変更がなければ保存しない）
+    pub fn update(state_dir: &Path, change: impl FnOnce(&mut Self) -> bool) -> Result<()> {
+        let _lock = StateLock::acquire(state_dir)?;
+        let mut state = Self::load(state_dir)?;
+        if change(&mut state) {
+            state.save(state_dir)?;
+        }
+        Ok(())
+    }
+
+    pub fn find_hook_run(&self, hook: &str, worktree: &Path) -> Option<&HookCacheEntry> {
+        self.hook_cache
+            .iter()
+            .find(|entry| entry.hook == hook && entry.worktree == worktree)
+    }
+
+    pub fn record_hook_run(&mut self, hook: &str, worktree: &Path, inputs_hash: &str) {
+        self.hook_cache
+            .retain(|entry| !(entry.hook == hook && entry.worktree == worktree));
+        self.hook_cache.push(HookCacheEntry {
+            hook: hook.to_string(),
+            worktree: worktree.to_path_buf(),
+            inputs_hash: inputs_hash.to_string(),
+        });
+    }
+}
+
+// フックのログを保存するディレクトリ（<state dir>/logs/<エスケープしたブランチ名>）
+pub fn log_dir(state_dir: &Path, branch: &str) -> PathBuf {
+    state_dir.join("logs").join(escape_branch(branch))
+}
+
+// ブランチ名を1つのディレクトリ名にする。'/'を'-'に置き換えると`feature/x`と`feature-x`が衝突するため、
+// '%'とパスに使えない文字をパーセントエンコードして元の名前と1対1に対応させる
+fn escape_branch(branch: &str) -> String {
+    let mut escaped = String::with_capacity(branch.len());
+    for c in branch.chars() {
+        match c {
+            '%' | '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => {
+                escaped.push_str(&format!("%{:02X}", c as u32));
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// worktreeの移動・改名に合わせて、キャッシュ状態・実行履歴・ログの場所を書き換える
+pub fn migrate_worktree(
+    state_dir: &Path,
+    old_path: &Path,
+    new_path: &Path,
+    renamed_branch: Option<(&str, &str)>,
+) -> Result<()> {
+    let _lock = StateLock::acquire(state_dir)?;
+    let mut state = GardenerState::load(state_dir)?;
+    let mut state_changed = false;
+    for entry in state.hook_cache.iter_mut().filter(|entry| entry.worktree == old_path) {
+        entry.worktree = new_path.to_path_buf();
+        state_changed = true;
+    }
+    if state_changed {
+        state.save(state_dir)?;
+    }
+    
+    // ログはブランチ名のディレクトリにあるので、改名時はディレクトリごと移す
+    let moved_logs = match renamed_branch {
+        Some((old_branch, new_branch)) => {
+            let (old_logs, new_logs) = (log_dir(state_dir, old_branch), log_dir(state_dir, new_branch));
+            if old_logs.exists() && !new_logs.exists() {
+                std::fs::rename(&old_logs, &new_logs)?;
+                Some((old_logs, new_logs))
+            } else {
+                None
+            }
+        }
+        None => None,
+    };
+    
+    let mut history = HookHistory::load(state_dir)?;
+    let mut history_changed = false;
+    for run in history.runs.iter_mut() {
+        if run.worktree_path == old_path {
+            run.worktree_path = new_path.to_path_buf();
+            if let Some((old_branch, new_branch)) = renamed_branch {
+                if run.worktree == old_branch {
+                    run.worktree = new_branch.to_string();
+                }
+            }
+            history_changed = true;
+        }
+        if let (Some((old_logs, new_logs)), Some(log_path)) = (&moved_logs, &mut run.log_path) {
+            if let Ok(relative) = log_path.strip_prefix(old_logs) {
+                *log_path = new_logs.join(relative);
+                history_changed = true;
+            }
+        }
+    }
+    if history_changed {
+        history.save(state_dir)?;
+    }
+    
+    Ok(())
+}
+
+// 削除されたworktreeのキャッシュ状態を取り除く（同じパスに作り直したworktreeがスキップされないように）
+// ディレクトリはもう存在しないので、残っている祖先までを実体のパスに解決して比べる
+pub fn forget_worktree(state_dir: &Path, worktree_path: &Path) -> Result<()> {
+    let removed = canonicalize_existing(worktree_path);
+    GardenerState::update(state_dir, |state| {
+        let before = state.hook_cache.len();
+        state
+            .hook_cache
+            .retain(|entry| entry.worktree != worktree_path && canonicalize_existing(&entry.worktree) != removed);
+        state.hook_cache.len() != before
+    })
+}
+
+// 状態ファイルの読み込み〜保存を複数のプロセスで直列化するロック（<state dir>/state.lock）
+pub struct StateLock {
+    path: PathBuf,
+}
+
+// ロックを待つ最大時間と、異常終了で残ったロックとみなすまでの時間
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+
+impl StateLock {
+    pub fn acquire(state_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(state_dir)?;
+        let path = state_dir.join("state.lock");
+        let started = Instant::now();
+
+        loop {
+            match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            let stale = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > STALE_LOCK_AGE));
+            if stale {
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+            if started.elapsed() > LOCK_TIMEOUT {
+                return Err(GitGardenerError::Custom(format!(
+                    "Timed out waiting for state lock: {}",
+                    path.display()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+// 書き込み途中のファイルが読まれないよう、一時ファイルに書いてからrenameする
+pub fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let tmp_path = path.with_extension(format!("tmp.{}", std::process::id()));
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_returns_default_when_missing() {
+        // What: 状態ファイルがない場合にデフォルトの状態が返されるかテスト
+        let temp_dir = tempdir().unwrap();
+
+        let state = GardenerState::load(temp_dir.path()).unwrap();
+
+        assert!(state.hook_cache.is_empty());
+    }
+
+    #[test]
+    fn test_record_hook_run_replaces_previous_entry() {
+        // What: 同じフック・worktreeの記録が上書きされ、保存・読み込みできるかテスト
+        let temp_dir = tempdir().unwrap();
+        let worktree = Path::new("/repo/.gardener/feature");
+
+        let mut state = GardenerState::default();
+        state.record_hook_run("hook-a", worktree, "hash-1");
+        state.record_hook_run("hook-a", worktree, "hash-2");
+        state.record_hook_run("hook-b", worktree, "hash-3");
+        state.save(temp_dir.path()).unwrap();
+
+        let loaded = GardenerState::load(temp_dir.path()).unwrap();
+
+        assert_eq!(loaded.hook_cache.len(), 2);
+        assert_eq!(loaded.find_hook_run("hook-a", worktree).unwrap().inputs_hash, "hash-2");
+        assert!(loaded.find_hook_run("hook-a", Path::new("/other")).is_none());
+    }
+
+    #[test]
+    fn test_hook_history_append_and_load() {
+        // What: フック実行履歴が追記・読み込みできるかテスト
+        let temp_dir = tempdir().unwrap();
+        let run = HookRun {
+            worktree: "feature".to_string(),
+            worktree_path: PathBuf::from("/repo/.gardener/feature"),
+            hook: "npm ci".to_string(),
+            started_at: 1_700_000_000,
+            duration_ms: 1_500,
+            status: HookRunStatus::Failed,
+            exit_code: Some(1),
+            log_path: None,
+        };
+
+        HookHistory::append(temp_dir.path(), run.clone()).unwrap();
+        HookHistory::append(temp_dir.path(), run.clone()).unwrap();
+
+        let history = HookHistory::load(temp_dir.path()).unwrap();
+        assert_eq!(history.runs.len(), 2);
+        assert_eq!(history.runs[0], run);
+    }
+
+    #[test]
+    fn test_migrate_worktree_updates_cache_entries() {
+        // What: worktreeの移動でキャッシュ状態のパスが書き換えられるかテスト
+        let temp_dir = tempdir().unwrap();
+        let old_path = Path::new("/repo/.gardener/old");
+        let new_path = Path::new("/repo/.gardener/new");
+        let mut state = GardenerState::default();
+        state.record_hook_run("hook-a", old_path, "hash-1");
+        state.record_hook_run("hook-a", Path::new("/other"), "hash-2");
+        state.save(temp_dir.path()).unwrap();
+
+        migrate_worktree(temp_dir.path(), old_path, new_path, None).unwrap();
+
+        let loaded = GardenerState::load(temp_dir.path()).unwrap();
+        assert!(loaded.find_hook_run("hook-a", old_path).is_none());
+        assert_eq!(loaded.find_hook_run("hook-a", new_path).unwrap().inputs_hash, "hash-1");
+        assert!(loaded.find_hook_run("hook-a", Path::new("/other")).is_some());
+    }
+
+    #[test]
+    fn test_forget_worktree_drops_cache_entries() {
+        // What: 削除したworktreeのキャッシュ状態だけが取り除かれるかテスト
+        let temp_dir = tempdir().unwrap();
+        let removed = Path::new("/repo/.gardener/removed");
+        let kept = Path::new("/repo/.gardener/kept");
+        let mut state = GardenerState::default();
+        state.record_hook_run("hook-a", removed, "hash-1");
+        state.record_hook_run("hook-a", kept, "hash-2");
+        state.save(temp_dir.path()).unwrap();
+
+        forget_worktree(temp_dir.path(), removed).unwrap();
+
+        let loaded = GardenerState::load(temp_dir.path()).unwrap();
+        assert!(loaded.find_hook_run("hook-a", removed).is_none());
+        assert!(loaded.find_hook_run("hook-a", kept).is_some());
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        // What: 複数スレッドから同時に更新しても、すべての記録が残るかテスト
+        let temp_dir = tempdir().unwrap();
+        let state_dir = temp_dir.path().to_path_buf();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let state_dir = state_dir.clone();
+                std::thread::spawn(move || {
+                    let worktree = PathBuf::from(format!("/repo/.gardener/w{}", i));
+                    GardenerState::update(&state_dir, |state| {
+                        state.record_hook_run("hook", &worktree, "hash");
+                        true
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let loaded = GardenerState::load(&state_dir).unwrap();
+        assert_eq!(loaded.hook_cache.len(), 8);
+        assert!(!state_dir.join("state.lock").exists());
+    }
+
+    #[test]
+    fn test_log_dir_keeps_branch_names_distinct() {
+        // What: '/'と'-'だけが異なるブランチのログディレクトリが衝突しないかテスト
+        let state_dir = Path::new("/repo/.git/gardener");
+
+        assert_ne!(log_dir(state_dir, "feature/x"), log_dir(state_dir, "feature-x"));
+        assert_ne!(log_dir(state_dir, "feature/x"), log_dir(state_dir, "feature%2Fx"));
+        assert_eq!(log_dir(state_dir, "feature/x"), state_dir.join("logs/feature%2Fx"));
+    }
+}
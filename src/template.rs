@@ -0,0 +1,459 @@
+use colored::*;
+use std::collections::HashMap;
+use crate::error::{GitGardenerError, Result};
+
+// `list --format`のテンプレート
+//
+//   {field}             フィールドの値（例: {branch}, {status.ahead}）
+//   {field:<20}         幅20で左寄せ（>は右寄せ、^は中央）。幅を省くと全行で最も長い値に揃える
+//   {field|red,bold}    値に色・スタイルを付ける
+//   {@yellow}...{/}     囲んだ部分に色・スタイルを付ける
+//   {?field}...{/}      フィールドが空・0・false・未取得でない場合だけ出力する（{!field}はその逆）
+//   {{ }} \t \n \\      波括弧・タブ・改行・バックスラッシュ
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Field(Placeholder),
+    Section { kind: Section, children: Vec<Node> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Section {
+    If(String),
+    Unless(String),
+    Style(Vec<Style>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder {
+    field: String,
+    align: Option<Align>,
+    styles: Vec<Style>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Align {
+    side: Side,
+    // Noneなら同じフィールドの全行で最も長い値に揃える
+    width: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Color(Color),
+    Bold,
+    Dimmed,
+    Italic,
+    Underline,
+}
+
+// テンプレートに渡す値
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Number(i64),
+    Flag(bool),
+    // ステータスが取得できなかった場合など
+    Missing,
+}
+
+impl Value {
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::Text(text) => !text.is_empty(),
+            Value::Number(number) => *number != 0,
+            Value::Flag(flag) => *flag,
+            Value::Missing => false,
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            Value::Flag(flag) => flag.to_string(),
+            Value::Missing => String::new(),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(flag: bool) -> Self {
+        Value::Flag(flag)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(number: u32) -> Self {
+        Value::Number(number as i64)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Missing, Into::into)
+    }
+}
+
+impl Template {
+    // fieldsにないフィールド名はエラーにする
+    pub fn parse(template: &str, fields: &[&str]) -> Result<Self> {
+        let error = |message: String| GitGardenerError::InvalidTemplate {
+            template: template.to_string(),
+            message,
+        };
+
+        // 開いているセクションのスタック（先頭はテンプレート全体）
+        let mut stack: Vec<(Option<Section>, Vec<Node>)> = vec![(None, Vec::new())];
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('t') => text.push('\t'),
+                    Some('n') => text.push('\n'),
+                    Some('\\') => text.push('\\'),
+                    Some(other) => {
+                        text.push('\\');
+                        text.push(other);
+                    }
+                    None => text.push('\\'),
+                },
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(error(format!("unclosed '{{{}'", tag))),
+                        }
+                    }
+                    let tag = tag.trim();
+
+                    let nodes = &mut stack.last_mut().expect("stack is never empty").1;
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+
+                    if tag == "/" {
+                        if stack.len() == 1 {
+                            return Err(error("'{/}' without an open section".to_string()));
+                        }
+                        let (kind, children) = stack.pop().expect("checked above");
+                        let kind = kind.expect("only the root has no section");
+                        stack.last_mut().expect("stack is never empty").1.push(Node::Section { kind, children });
+                    } else if let Some(field) = tag.strip_prefix('?') {
+                        stack.push((Some(Section::If(check_field(field.trim(), fields).map_err(error)?)), Vec::new()));
+                    } else if let Some(field) = tag.strip_prefix('!') {
+                        stack.push((Some(Section::Unless(check_field(field.trim(), fields).map_err(error)?)), Vec::new()));
+                    } else if let Some(styles) = tag.strip_prefix('@') {
+                        stack.push((Some(Section::Style(parse_styles(styles).map_err(error)?)), Vec::new()));
+                    } else {
+                        nodes.push(Node::Field(parse_placeholder(tag, fields).map_err(error)?));
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if stack.len() > 1 {
+            return Err(error("section is not closed with '{/}'".to_string()));
+        }
+        let mut nodes = stack.pop().expect("stack is never empty").1;
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Ok(Self { nodes })
+    }
+
+    // 使われているフィールド名（必要なデータだけを取得するため）
+    pub fn fields(&self) -> Vec<&str> {
+        fn collect<'a>(nodes: &'a [Node], fields: &mut Vec<&'a str>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Field(placeholder) => fields.push(&placeholder.field),
+                    Node::Section { kind, children } => {
+                        if let Section::If(field) | Section::Unless(field) = kind {
+                            fields.push(field);
+                        }
+                        collect(children, fields);
+                    }
+                }
+            }
+        }
+
+        let mut fields = Vec::new();
+        collect(&self.nodes, &mut fields);
+        fields
+    }
+
+    // 各行を描画する（幅を省いた揃えのため、先に全行の値の長さを調べる）
+    pub fn render_all<T>(&self, items: &[T], lookup: impl Fn(&T, &str) -> Value) -> Vec<String> {
+        let mut widths: HashMap<&str, usize> = HashMap::new();
+        for placeholder in self.placeholders() {
+            if placeholder.align.is_some_and(|align| align.width.is_none()) {
+                let width = items
+                    .iter()
+                    .map(|item| lookup(item, &placeholder.field).render().chars().count())
+                    .max()
+                    .unwrap_or(0);
+                widths.insert(&placeholder.field, width);
+            }
+        }
+
+        items
+            .iter()
+            .map(|item| {
+                let mut output = String::new();
+                render_nodes(&self.nodes, &|field| lookup(item, field), &widths, &mut output);
+                output
+            })
+            .collect()
+    }
+
+    fn placeholders(&self) -> Vec<&Placeholder> {
+        fn collect<'a>(nodes: &'a [Node], placeholders: &mut Vec<&'a Placeholder>) {
+            for node in nodes {
+                match node {
+                    Node::Text(_) => {}
+                    Node::Field(placeholder) => placeholders.push(placeholder),
+                    Node::Section { children, .. } => collect(children, placeholders),
+                }
+            }
+        }
+
+        let mut placeholders = Vec::new();
+        collect(&self.nodes, &mut placeholders);
+        placeholders
+    }
+}
+
+fn render_nodes(nodes: &[Node], lookup: &dyn Fn(&str) -> Value, widths: &HashMap<&str, usize>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Field(placeholder) => {
+                let mut value = lookup(&placeholder.field).render();
+                if let Some(align) = placeholder.align {
+                    let width = align.width.or_else(|| widths.get(placeholder.field.as_str()).copied()).unwrap_or(0);
+                    value = pad(&value, width, align.side);
+                }
+                output.push_str(&paint(value, &placeholder.styles));
+            }
+            Node::Section { kind, children } => match kind {
+                Section::If(field) if !lookup(field).is_truthy() => {}
+                Section::Unless(field) if lookup(field).is_truthy() => {}
+                Section::Style(styles) => {
+                    let mut inner = String::new();
+                    render_nodes(children, lookup, widths, &mut inner);
+                    output.push_str(&paint(inner, styles));
+                }
+                _ => render_nodes(children, lookup, widths, output),
+            },
+        }
+    }
+}
+
+fn pad(value: &str, width: usize, side: Side) -> String {
+    let fill = width.saturating_sub(value.chars().count());
+    match side {
+        Side::Left => format!("{}{}", value, " ".repeat(fill)),
+        Side::Right => format!("{}{}", " ".repeat(fill), value),
+        Side::Center => format!("{}{}{}", " ".repeat(fill / 2), value, " ".repeat(fill - fill / 2)),
+    }
+}
+
+// 色の出力はcoloredの設定（NO_COLORや端末かどうか）に従う
+fn paint(text: String, styles: &[Style]) -> String {
+    if styles.is_empty() || text.is_empty() {
+        return text;
+    }
+    let painted = styles.iter().fold(text.normal(), |painted, style| match style {
+        Style::Color(color) => painted.color(*color),
+        Style::Bold => painted.bold(),
+        Style::Dimmed => painted.dimmed(),
+        Style::Italic => painted.italic(),
+        Style::Underline => painted.underline(),
+    });
+    painted.to_string()
+}
+
+fn check_field(field: &str, fields: &[&str]) -> std::result::Result<String, String> {
+    if fields.contains(&field) {
+        Ok(field.to_string())
+    } else {
+        Err(format!("unknown field '{}' (available: {})", field, fields.join(", ")))
+    }
+}
+
+// `field`、`field:<20`、`field|red,bold`、`field:>|cyan`
+fn parse_placeholder(tag: &str, fields: &[&str]) -> std::result::Result<Placeholder, String> {
+    let (spec, styles) = match tag.split_once('|') {
+        Some((spec, styles)) => (spec, parse_styles(styles)?),
+        None => (tag, Vec::new()),
+    };
+    let (field, align) = match spec.split_once(':') {
+        Some((field, align)) => (field, Some(parse_align(align.trim())?)),
+        None => (spec, None),
+    };
+
+    Ok(Placeholder {
+        field: check_field(field.trim(), fields)?,
+        align,
+        styles,
+    })
+}
+
+fn parse_align(spec: &str) -> std::result::Result<Align, String> {
+    let (side, width) = match spec.chars().next() {
+        Some('<') => (Side::Left, &spec[1..]),
+        Some('>') => (Side::Right, &spec[1..]),
+        Some('^') => (Side::Center, &spec[1..]),
+        _ => (Side::Left, spec),
+    };
+    let width = if width.is_empty() {
+        None
+    } else {
+        Some(width.parse().map_err(|_| format!("invalid alignment '{}'", spec))?)
+    };
+    Ok(Align { side, width })
+}
+
+fn parse_styles(styles: &str) -> std::result::Result<Vec<Style>, String> {
+    styles
+        .split(',')
+        .map(str::trim)
+        .filter(|style| !style.is_empty())
+        .map(|style| match style {
+            "bold" => Ok(Style::Bold),
+            "dim" | "dimmed" => Ok(Style::Dimmed),
+            "italic" => Ok(Style::Italic),
+            "underline" => Ok(Style::Underline),
+            // bright_black・bright-blackのような書き方も受け付ける
+            color => color
+                .replace(['_', '-'], " ")
+                .parse::<Color>()
+                .map(Style::Color)
+                .map_err(|_| format!("unknown colour or style '{}'", color)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: [&str; 4] = ["branch", "path", "status.ahead", "locked"];
+
+    fn lookup(row: &(&str, i64, bool), field: &str) -> Value {
+        match field {
+            "branch" => Value::Text(row.0.to_string()),
+            "path" => Value::Text(format!("/repo/{}", row.0)),
+            "status.ahead" => Value::Number(row.1),
+            "locked" => Value::Flag(row.2),
+            _ => Value::Missing,
+        }
+    }
+
+    fn render(template: &str) -> Vec<String> {
+        colored::control::set_override(false);
+        let rows = [("main", 0, false), ("feature/long", 2, true)];
+        Template::parse(template, &FIELDS).unwrap().render_all(&rows, lookup)
+    }
+
+    #[test]
+    fn test_render_fields_and_escapes() {
+        // What: フィールドの値とタブ・波括弧のエスケープが出力されるかテスト
+        assert_eq!(
+            render(r"{branch}\t{path}\t{status.ahead} {{x}}"),
+            vec!["main\t/repo/main\t0 {x}", "feature/long\t/repo/feature/long\t2 {x}"]
+        );
+    }
+
+    #[test]
+    fn test_render_alignment() {
+        // What: 幅指定の揃えと、幅を省いた場合の全行での揃えをテスト
+        assert_eq!(render("[{branch:<}] [{status.ahead:>3}] [{locked:^7}]"), vec![
+            "[main        ] [  0] [ false ]",
+            "[feature/long] [  2] [ true  ]",
+        ]);
+    }
+
+    #[test]
+    fn test_render_conditional_sections() {
+        // What: 条件付きセクションが値の有無で出力・省略されるかテスト
+        assert_eq!(
+            render("{branch}{?status.ahead} ↑{status.ahead}{/}{!locked} (unlocked){/}"),
+            vec!["main (unlocked)", "feature/long ↑2"]
+        );
+    }
+
+    #[test]
+    fn test_parse_colours() {
+        // What: フィールドとセクションの色・スタイルの指定が読み取れるかテスト
+        // （色の出力自体はcoloredのグローバルな設定に依存するので、他のテストと並行して確かめない）
+        let template = Template::parse("{branch|green,bold}{@bright_black} {path:>}{/}", &FIELDS).unwrap();
+
+        assert_eq!(template.nodes, vec![
+            Node::Field(Placeholder {
+                field: "branch".to_string(),
+                align: None,
+                styles: vec![Style::Color(Color::Green), Style::Bold],
+            }),
+            Node::Section {
+                kind: Section::Style(vec![Style::Color(Color::BrightBlack)]),
+                children: vec![
+                    Node::Text(" ".to_string()),
+                    Node::Field(Placeholder {
+                        field: "path".to_string(),
+                        align: Some(Align { side: Side::Right, width: None }),
+                        styles: vec![],
+                    }),
+                ],
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // What: 不明なフィールド・色や閉じていないセクションがエラーになるかテスト
+        for template in ["{unknown}", "{branch|sparkly}", "{?locked}x", "x{/}", "{branch", "{branch:<abc}"] {
+            let result = Template::parse(template, &FIELDS);
+            assert!(
+                matches!(result, Err(GitGardenerError::InvalidTemplate { .. })),
+                "{} should be rejected",
+                template
+            );
+        }
+        let template = Template::parse("{?locked}{branch}{/}{path:<}", &FIELDS).unwrap();
+        assert_eq!(template.fields(), vec!["locked", "branch", "path"]);
+    }
+}
@@ -0,0 +1,45 @@
+// テストで使うGitリポジトリの準備（gitコマンドで作り、ユーザー設定はリポジトリごとに行う）
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::{tempdir, TempDir};
+
+pub fn git(dir: &Path, args: &[&str]) -> Output {
+    Command::new("git").args(args).current_dir(dir).output().unwrap()
+}
+
+// mainブランチで初期化し、コミットできるようユーザーを設定する
+pub fn init_repo(path: &Path) {
+    git(path, &["init", "-q", "-b", "main"]);
+    git(path, &["config", "user.name", "Test User"]);
+    git(path, &["config", "user.email", "test@example.com"]);
+}
+
+// すべての変更をコミットする
+pub fn commit_all(dir: &Path, message: &str) {
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+// README.mdだけを初期コミットしたリポジトリ
+pub fn setup_git_repo() -> TempDir {
+    let temp_dir = tempdir().unwrap();
+    init_repo(temp_dir.path());
+    fs::write(temp_dir.path().join("README.md"), "# Test Repo").unwrap();
+    commit_all(temp_dir.path(), "Initial commit");
+    temp_dir
+}
+
+// リポジトリからの相対パスに新しいブランチのworktreeを作る
+pub fn add_worktree(repo_path: &Path, branch: &str, relative: &str) -> PathBuf {
+    let worktree_path = repo_path.join(relative);
+    git(repo_path, &["worktree", "add", "-q", "-b", branch, &worktree_path.to_string_lossy()]);
+    worktree_path
+}
+
+// setup_git_repoにfeature-testブランチのworktree（<repo>/feature-test）を加えたもの
+pub fn setup_git_repo_with_worktree() -> TempDir {
+    let temp_dir = setup_git_repo();
+    add_worktree(temp_dir.path(), "feature-test", "feature-test");
+    temp_dir
+}
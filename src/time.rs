@@ -0,0 +1,95 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// UNIX時刻を `YYYY-MM-DD HH:MM:SS`（UTC）に整形する
+pub fn format_timestamp(secs: i64) -> String {
+    let days = secs.div_euclid(86_400);
+    let seconds_of_day = secs.rem_euclid(86_400);
+
+    // Howard Hinnantのcivil_from_daysアルゴリズム
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// 経過時間を `850ms` / `12.3s` / `4m05s` の形式に整形する
+pub fn format_duration(millis: u64) -> String {
+    if millis < 1_000 {
+        format!("{}ms", millis)
+    } else if millis < 60_000 {
+        format!("{:.1}s", millis as f64 / 1_000.0)
+    } else {
+        let secs = millis / 1_000;
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+// 経過秒数を `45s` / `12m` / `3h` / `5d` / `2w` / `4mo` / `1y` の形式に整形する
+pub fn format_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3_599 => format!("{}m", secs / 60),
+        3_600..=86_399 => format!("{}h", secs / 3_600),
+        86_400..=1_209_599 => format!("{}d", secs / 86_400),
+        1_209_600..=5_183_999 => format!("{}w", secs / 604_800),
+        5_184_000..=31_535_999 => format!("{}mo", secs / 2_592_000),
+        _ => format!("{}y", secs / 31_536_000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_timestamp() {
+        // What: UNIX時刻が正しい日時文字列に変換されるかテスト
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13:20");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        // What: 経過時間が単位付きで整形されるかテスト
+        assert_eq!(format_duration(850), "850ms");
+        assert_eq!(format_duration(12_340), "12.3s");
+        assert_eq!(format_duration(245_000), "4m05s");
+    }
+
+    #[test]
+    fn test_format_age() {
+        // What: 経過時間が最も大きい単位で整形されるかテスト
+        assert_eq!(format_age(-5), "0s");
+        assert_eq!(format_age(45), "45s");
+        assert_eq!(format_age(125), "2m");
+        assert_eq!(format_age(7_200), "2h");
+        assert_eq!(format_age(3 * 86_400), "3d");
+        assert_eq!(format_age(21 * 86_400), "3w");
+        assert_eq!(format_age(90 * 86_400), "3mo");
+        assert_eq!(format_age(800 * 86_400), "2y");
+    }
+}